@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE servers DROP COLUMN version;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE servers ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
\ No newline at end of file
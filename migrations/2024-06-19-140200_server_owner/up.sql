@@ -0,0 +1,6 @@
+-- Your SQL goes here
+-- Rows created before ownership existed keep a NULL owner and stay hidden
+-- until an operator assigns them.
+ALTER TABLE servers ADD COLUMN owner_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX servers_owner_id_idx ON servers (owner_id);
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX servers_ip_idx;
+
+ALTER TABLE servers ALTER COLUMN ip TYPE VARCHAR(255) USING abbrev(ip);
\ No newline at end of file
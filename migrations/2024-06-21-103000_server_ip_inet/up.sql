@@ -0,0 +1,7 @@
+-- Your SQL goes here
+-- Rows holding values that are not valid addresses (e.g. "10.0.0.300") make this
+-- cast fail; fix them first with:
+--   SELECT id, name, ip FROM servers WHERE ip !~ '^[0-9a-fA-F:.]+(/[0-9]{1,3})?$';
+ALTER TABLE servers ALTER COLUMN ip TYPE inet USING ip::inet;
+
+CREATE INDEX servers_ip_idx ON servers USING gist (ip inet_ops);
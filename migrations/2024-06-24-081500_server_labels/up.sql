@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE servers ADD COLUMN labels JSONB NOT NULL DEFAULT '{}';
+
+CREATE INDEX servers_labels_idx ON servers USING gin (labels);
\ No newline at end of file
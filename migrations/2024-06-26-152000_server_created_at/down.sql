@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX servers_owner_created_at_idx;
+DROP INDEX servers_owner_ip_idx;
+DROP INDEX servers_owner_name_idx;
+
+ALTER TABLE servers DROP COLUMN created_at;
\ No newline at end of file
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE servers ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- Keyset pagination walks (sort column, id) within one owner's servers.
+CREATE INDEX servers_owner_name_idx ON servers (owner_id, name, id);
+CREATE INDEX servers_owner_ip_idx ON servers (owner_id, ip, id);
+CREATE INDEX servers_owner_created_at_idx ON servers (owner_id, created_at, id);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX servers_search_trgm_idx;
+
+DROP FUNCTION server_search_text(VARCHAR, INET, JSONB);
\ No newline at end of file
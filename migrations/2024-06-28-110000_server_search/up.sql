@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- Lower-cased "name host key=value ..." document that `q=` searches match against.
+CREATE FUNCTION server_search_text(name VARCHAR, ip INET, labels JSONB) RETURNS TEXT AS $$
+    SELECT lower(
+        name || ' ' || host(ip) || ' ' ||
+        coalesce((SELECT string_agg(key || '=' || value, ' ') FROM jsonb_each_text(labels)), '')
+    )
+$$ LANGUAGE sql IMMUTABLE PARALLEL SAFE;
+
+CREATE INDEX servers_search_trgm_idx ON servers
+    USING gin (server_search_text(name, ip, labels) gin_trgm_ops);
@@ -0,0 +1,4 @@
+-- Your SQL goes here
+ALTER TABLE servers ADD COLUMN deleted_at TIMESTAMPTZ;
+
+CREATE INDEX servers_deleted_at_idx ON servers (deleted_at) WHERE deleted_at IS NOT NULL;
\ No newline at end of file
@@ -0,0 +1,12 @@
+-- Your SQL goes here
+CREATE TABLE server_history (
+    id BIGSERIAL PRIMARY KEY,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    before JSONB,
+    after JSONB,
+    UNIQUE (server_id, revision)
+);
@@ -0,0 +1,7 @@
+DROP INDEX user_tokens_user_id_idx;
+DROP INDEX user_tokens_token_key;
+
+ALTER TABLE user_tokens
+    DROP COLUMN last_used_at,
+    DROP COLUMN created_at,
+    DROP COLUMN name;
@@ -0,0 +1,8 @@
+-- user_tokens becomes the store for API keys; `token` holds the SHA-256 of the key.
+ALTER TABLE user_tokens
+    ADD COLUMN name VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN last_used_at TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX user_tokens_token_key ON user_tokens (token);
+CREATE INDEX user_tokens_user_id_idx ON user_tokens (user_id);
@@ -0,0 +1,3 @@
+DROP TABLE server_checks;
+
+ALTER TABLE servers DROP COLUMN check_ports;
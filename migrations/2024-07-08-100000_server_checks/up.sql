@@ -0,0 +1,11 @@
+-- Ports the health checker connects to; empty means the configured defaults.
+ALTER TABLE servers ADD COLUMN check_ports INTEGER[] NOT NULL DEFAULT '{}';
+
+-- Latest health check result per server.
+CREATE TABLE server_checks (
+    server_id UUID PRIMARY KEY REFERENCES servers(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL,
+    latency_ms INTEGER,
+    ports JSONB NOT NULL DEFAULT '[]',
+    checked_at TIMESTAMPTZ NOT NULL
+);
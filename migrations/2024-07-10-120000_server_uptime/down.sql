@@ -0,0 +1,3 @@
+DROP TABLE server_outages;
+DROP TABLE server_check_rollups;
+DROP TABLE server_check_samples;
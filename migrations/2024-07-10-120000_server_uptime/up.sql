@@ -0,0 +1,34 @@
+-- Every health check outcome, pruned after UPTIME_RAW_RETENTION_HOURS.
+CREATE TABLE server_check_samples (
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    checked_at TIMESTAMPTZ NOT NULL,
+    up BOOLEAN NOT NULL,
+    latency_ms INTEGER,
+    PRIMARY KEY (server_id, checked_at)
+);
+
+CREATE INDEX server_check_samples_checked_at_idx ON server_check_samples (checked_at);
+
+-- Hourly downsampling of the samples, pruned after UPTIME_ROLLUP_RETENTION_DAYS.
+CREATE TABLE server_check_rollups (
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    samples INTEGER NOT NULL,
+    up_samples INTEGER NOT NULL,
+    latency_p50_ms INTEGER,
+    latency_p95_ms INTEGER,
+    PRIMARY KEY (server_id, bucket_start)
+);
+
+CREATE INDEX server_check_rollups_bucket_start_idx ON server_check_rollups (bucket_start);
+
+-- Periods in which no checked port answered; `ended_at` is NULL while ongoing.
+CREATE TABLE server_outages (
+    id BIGSERIAL PRIMARY KEY,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX server_outages_open_idx ON server_outages (server_id) WHERE ended_at IS NULL;
+CREATE INDEX server_outages_server_id_started_at_idx ON server_outages (server_id, started_at);
@@ -0,0 +1,14 @@
+-- What runs on a server; removed together with it.
+CREATE TABLE server_services (
+    id UUID PRIMARY KEY,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    port INTEGER NOT NULL CHECK (port BETWEEN 1 AND 65535),
+    protocol VARCHAR(8) NOT NULL DEFAULT 'tcp',
+    team VARCHAR(255),
+    url TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (server_id, port, protocol)
+);
+
+CREATE INDEX server_services_lower_name_idx ON server_services (lower(name));
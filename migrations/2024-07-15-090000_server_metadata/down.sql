@@ -0,0 +1,3 @@
+DROP TABLE metadata_schemas;
+
+ALTER TABLE servers DROP COLUMN metadata;
@@ -0,0 +1,14 @@
+-- Free-form attributes, e.g. {"rack": "R12", "cost_center": "cc-42"}.
+ALTER TABLE servers ADD COLUMN metadata JSONB NOT NULL DEFAULT '{}';
+
+-- JSON Schemas the metadata of servers matched by `label_selector` must satisfy.
+-- An empty selector applies to every server.
+CREATE TABLE metadata_schemas (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    label_selector TEXT NOT NULL DEFAULT '',
+    schema JSONB NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,3 @@
+DROP TABLE server_dns_checks;
+
+ALTER TABLE servers DROP COLUMN hostname;
@@ -0,0 +1,14 @@
+ALTER TABLE servers ADD COLUMN hostname VARCHAR(253);
+
+-- Latest DNS lookups per server. `hostname` and `ip` are the values that were
+-- looked up, so results made stale by a later change can be told apart.
+CREATE TABLE server_dns_checks (
+    server_id UUID PRIMARY KEY REFERENCES servers(id) ON DELETE CASCADE,
+    hostname VARCHAR(253),
+    ip INET NOT NULL,
+    addresses INET[] NOT NULL DEFAULT '{}',
+    forward_error TEXT,
+    ptr_names TEXT[] NOT NULL DEFAULT '{}',
+    reverse_error TEXT,
+    checked_at TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,14 @@
+-- Planned downtime of a server. With `rrule` (e.g. `FREQ=WEEKLY;BYDAY=SU`) the
+-- window repeats, every occurrence lasting as long as the first one.
+CREATE TABLE server_maintenance_windows (
+    id UUID PRIMARY KEY,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL CHECK (ends_at > starts_at),
+    rrule TEXT,
+    reason TEXT,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX server_maintenance_windows_server_id_idx ON server_maintenance_windows (server_id);
@@ -0,0 +1,12 @@
+-- `server_id` stops working when `depends_on_id` goes down. The graph is kept
+-- acyclic by the application.
+CREATE TABLE server_dependencies (
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    depends_on_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (server_id, depends_on_id),
+    CHECK (server_id <> depends_on_id)
+);
+
+CREATE INDEX server_dependencies_depends_on_id_idx ON server_dependencies (depends_on_id);
@@ -0,0 +1,13 @@
+-- Address ranges servers get their addresses allocated from. Subnets of one
+-- owner never overlap, which the application checks on insert.
+CREATE TABLE subnets (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    cidr CIDR NOT NULL,
+    gateway INET,
+    vlan INTEGER CHECK (vlan BETWEEN 1 AND 4094),
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (owner_id, cidr),
+    CHECK (gateway IS NULL OR gateway << cidr)
+);
@@ -0,0 +1,2 @@
+DROP TABLE server_secret_reveals;
+DROP TABLE server_secrets;
@@ -0,0 +1,26 @@
+-- Credentials of a server, sealed with AES-256-GCM under `VAULT_MASTER_KEY`.
+-- The nonce is random per write; server id and name are bound in as
+-- associated data so a ciphertext only opens under its own entry.
+CREATE TABLE server_secrets (
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    nonce BYTEA NOT NULL,
+    ciphertext BYTEA NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (server_id, name)
+);
+
+-- Every attempt to read a secret in clear text, kept after the secret or the
+-- server is gone.
+CREATE TABLE server_secret_reveals (
+    id BIGSERIAL PRIMARY KEY,
+    server_id UUID NOT NULL,
+    secret_name VARCHAR(255) NOT NULL,
+    actor_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    succeeded BOOLEAN NOT NULL,
+    revealed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX server_secret_reveals_server_id_idx ON server_secret_reveals (server_id, revealed_at);
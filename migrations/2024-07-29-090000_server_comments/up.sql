@@ -0,0 +1,12 @@
+-- A discussion thread per server. `body` is markdown, rendered on read;
+-- `edited_at` is set when the author changes it.
+CREATE TABLE server_comments (
+    id UUID PRIMARY KEY,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    author_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    edited_at TIMESTAMPTZ
+);
+
+CREATE INDEX server_comments_server_id_idx ON server_comments (server_id, created_at);
@@ -0,0 +1,2 @@
+DROP TABLE server_pins;
+DROP TABLE saved_views;
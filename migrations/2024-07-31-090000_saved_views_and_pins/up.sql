@@ -0,0 +1,18 @@
+-- Named `list_servers` filters per user. `filter` holds the query parameters
+-- of the view as a JSON object of strings, e.g. {"label_selector": "env=prod"}.
+CREATE TABLE saved_views (
+    id UUID PRIMARY KEY,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    filter JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (owner_id, name)
+);
+
+-- Servers a user keeps at the top of their listings.
+CREATE TABLE server_pins (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    server_id UUID NOT NULL REFERENCES servers(id) ON DELETE CASCADE,
+    pinned_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, server_id)
+);
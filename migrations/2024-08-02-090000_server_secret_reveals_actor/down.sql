@@ -0,0 +1 @@
+DROP INDEX server_secret_reveals_actor_id_idx;
@@ -0,0 +1,2 @@
+-- Recent failed reveals of a user are counted before every reveal.
+CREATE INDEX server_secret_reveals_actor_id_idx ON server_secret_reveals (actor_id, revealed_at);
@@ -1,8 +1,12 @@
 use std::env;
+use std::fmt;
 
 use dotenvy::dotenv;
 use tokio::sync::OnceCell;
 
+use crate::domains::models::server::AddressUniqueness;
+use crate::infra::vault::parse_master_key;
+
 #[derive(Debug)]
 struct ServerConfig {
     host: String,
@@ -14,10 +18,72 @@ struct DatabaseConfig {
     url: String,
 }
 
+#[derive(Debug)]
+struct AddressConfig {
+    uniqueness: AddressUniqueness,
+}
+
+#[derive(Debug)]
+struct TrashConfig {
+    retention_days: i64,
+    purge_interval_secs: u64,
+}
+
+#[derive(Debug)]
+struct HealthCheckConfig {
+    interval_secs: u64,
+    timeout_ms: u64,
+    concurrency: usize,
+    ports: Vec<u16>,
+}
+
+#[derive(Debug)]
+struct DnsCheckConfig {
+    interval_secs: u64,
+    timeout_ms: u64,
+    hosts_file: Option<String>,
+}
+
+#[derive(Debug)]
+struct UptimeConfig {
+    raw_retention_hours: i64,
+    rollup_retention_days: i64,
+}
+
+#[derive(Debug)]
+struct AdminConfig {
+    usernames: Vec<String>,
+}
+
+#[derive(Debug)]
+struct PrometheusSdConfig {
+    ports: Vec<u16>,
+}
+
+struct VaultConfig {
+    master_key: Option<[u8; 32]>,
+}
+
+impl fmt::Debug for VaultConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VaultConfig")
+            .field("master_key", &self.master_key.map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 pub struct Config {
     server: ServerConfig,
     db: DatabaseConfig,
+    address: AddressConfig,
+    trash: TrashConfig,
+    health_check: HealthCheckConfig,
+    uptime: UptimeConfig,
+    dns_check: DnsCheckConfig,
+    prometheus_sd: PrometheusSdConfig,
+    admin: AdminConfig,
+    vault: VaultConfig,
 }
 
 impl Config {
@@ -32,6 +98,72 @@ impl Config {
     pub fn server_port(&self) -> u16 {
         self.server.port
     }
+
+    pub fn address_uniqueness(&self) -> AddressUniqueness {
+        self.address.uniqueness
+    }
+
+    pub fn trash_retention(&self) -> chrono::Duration {
+        chrono::Duration::days(self.trash.retention_days)
+    }
+
+    pub fn trash_purge_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.trash.purge_interval_secs)
+    }
+
+    pub fn health_check_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.health_check.interval_secs)
+    }
+
+    pub fn health_check_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.health_check.timeout_ms)
+    }
+
+    pub fn health_check_concurrency(&self) -> usize {
+        self.health_check.concurrency
+    }
+
+    /// Ports checked on servers that do not list their own `check_ports`.
+    pub fn health_check_ports(&self) -> &[u16] {
+        &self.health_check.ports
+    }
+
+    pub fn dns_check_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.dns_check.interval_secs)
+    }
+
+    pub fn dns_check_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.dns_check.timeout_ms)
+    }
+
+    /// Resolve from this hosts(5) file only instead of the system resolver.
+    pub fn dns_hosts_file(&self) -> Option<&str> {
+        self.dns_check.hosts_file.as_deref()
+    }
+
+    /// How long every single check is kept before only hourly rollups remain.
+    pub fn uptime_raw_retention(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.uptime.raw_retention_hours)
+    }
+
+    pub fn uptime_rollup_retention(&self) -> chrono::Duration {
+        chrono::Duration::days(self.uptime.rollup_retention_days)
+    }
+
+    /// Users allowed to manage settings shared by everyone, such as metadata schemas.
+    pub fn admin_usernames(&self) -> &[String] {
+        &self.admin.usernames
+    }
+
+    /// Ports scraped on every server when the request does not name any.
+    pub fn prometheus_sd_ports(&self) -> &[u16] {
+        &self.prometheus_sd.ports
+    }
+
+    /// Key the server secrets are sealed with; without one the vault is off.
+    pub fn vault_master_key(&self) -> Option<&[u8; 32]> {
+        self.vault.master_key.as_ref()
+    }
 }
 
 pub static CONFIG: OnceCell<Config> = OnceCell::const_new();
@@ -50,9 +182,111 @@ async fn init_config() -> Config {
         url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
     };
 
+    let address_config = AddressConfig {
+        uniqueness: env::var("SERVER_ADDRESS_UNIQUENESS")
+            .unwrap_or_else(|_| String::from("owner"))
+            .parse::<AddressUniqueness>()
+            .unwrap(),
+    };
+
+    let trash_config = TrashConfig {
+        retention_days: env::var("TRASH_RETENTION_DAYS")
+            .unwrap_or_else(|_| String::from("30"))
+            .parse::<i64>()
+            .unwrap(),
+        purge_interval_secs: env::var("TRASH_PURGE_INTERVAL_SECS")
+            .unwrap_or_else(|_| String::from("3600"))
+            .parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("TRASH_PURGE_INTERVAL_SECS must be a positive number of seconds"),
+    };
+
+    let health_check_config = HealthCheckConfig {
+        interval_secs: env::var("HEALTH_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| String::from("60"))
+            .parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("HEALTH_CHECK_INTERVAL_SECS must be a positive number of seconds"),
+        timeout_ms: env::var("HEALTH_CHECK_TIMEOUT_MS")
+            .unwrap_or_else(|_| String::from("2000"))
+            .parse::<u64>()
+            .unwrap(),
+        concurrency: env::var("HEALTH_CHECK_CONCURRENCY")
+            .unwrap_or_else(|_| String::from("32"))
+            .parse::<usize>()
+            .unwrap(),
+        ports: env::var("HEALTH_CHECK_PORTS")
+            .unwrap_or_else(|_| String::from("22"))
+            .split(',')
+            .map(|port| port.trim().parse::<u16>().unwrap())
+            .collect(),
+    };
+
+    let uptime_config = UptimeConfig {
+        raw_retention_hours: env::var("UPTIME_RAW_RETENTION_HOURS")
+            .unwrap_or_else(|_| String::from("48"))
+            .parse::<i64>()
+            .unwrap(),
+        rollup_retention_days: env::var("UPTIME_ROLLUP_RETENTION_DAYS")
+            .unwrap_or_else(|_| String::from("90"))
+            .parse::<i64>()
+            .unwrap(),
+    };
+
+    let dns_check_config = DnsCheckConfig {
+        interval_secs: env::var("DNS_CHECK_INTERVAL_SECS")
+            .unwrap_or_else(|_| String::from("3600"))
+            .parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .expect("DNS_CHECK_INTERVAL_SECS must be a positive number of seconds"),
+        timeout_ms: env::var("DNS_CHECK_TIMEOUT_MS")
+            .unwrap_or_else(|_| String::from("3000"))
+            .parse::<u64>()
+            .unwrap(),
+        hosts_file: env::var("DNS_HOSTS_FILE")
+            .ok()
+            .filter(|path| !path.is_empty()),
+    };
+
+    let prometheus_sd_config = PrometheusSdConfig {
+        ports: env::var("PROMETHEUS_SD_PORTS")
+            .unwrap_or_else(|_| String::from("9100"))
+            .split(',')
+            .map(|port| port.trim().parse::<u16>().unwrap())
+            .collect(),
+    };
+
+    let admin_config = AdminConfig {
+        usernames: env::var("ADMIN_USERNAMES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|username| !username.is_empty())
+            .map(str::to_string)
+            .collect(),
+    };
+
+    let vault_config = VaultConfig {
+        master_key: env::var("VAULT_MASTER_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+            .map(|key| parse_master_key(&key).unwrap()),
+    };
+
     Config {
         server: server_config,
         db: database_config,
+        address: address_config,
+        trash: trash_config,
+        health_check: health_check_config,
+        uptime: uptime_config,
+        dns_check: dns_check_config,
+        prometheus_sd: prometheus_sd_config,
+        admin: admin_config,
+        vault: vault_config,
     }
 }
 
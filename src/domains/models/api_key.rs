@@ -0,0 +1,70 @@
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+use crate::utils::sha256_hex;
+
+/// Every API key starts with this, which tells them apart from session JWTs.
+pub const API_KEY_PREFIX: &str = "inv_";
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyModel {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A new random key. Only its hash is stored, the key itself is shown once.
+pub fn generate_api_key() -> String {
+    format!(
+        "{}{}{}",
+        API_KEY_PREFIX,
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+pub fn hash_api_key(key: &str) -> String {
+    sha256_hex(key.as_bytes())
+}
+
+#[derive(Debug)]
+pub enum ApiKeyError {
+    InfraError(InfraError),
+    NotFound,
+    BadRequest(String),
+}
+
+impl From<InfraError> for ApiKeyError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => ApiKeyError::NotFound,
+            _ => ApiKeyError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for ApiKeyError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("ApiKeyModel with id has not been found"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"ApiKeyModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
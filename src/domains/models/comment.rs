@@ -0,0 +1,393 @@
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+#[derive(Debug, Clone)]
+pub struct CommentModel {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    /// `None` once the author's account is gone.
+    pub author_id: Option<Uuid>,
+    pub author: Option<String>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+pub fn check_body(body: &str) -> Result<(), String> {
+    if body.trim().is_empty() || body.len() > 10000 {
+        return Err(String::from("A comment takes 1 to 10000 characters"));
+    }
+    Ok(())
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders the markdown subset comments use: paragraphs, `- ` lists, fenced
+/// code blocks, `code`, **bold**, *italic* and [links](https://...). Any HTML
+/// in the source is escaped, and links only keep http, https and mailto targets.
+pub fn render_markdown(body: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+
+    for line in body.lines() {
+        if let Some(code_lines) = code.as_mut() {
+            if line.trim_start().starts_with("```") {
+                html.push_str(&format!(
+                    "<pre><code>{}</code></pre>",
+                    escape_html(&code_lines.join("\n"))
+                ));
+                code = None;
+            } else {
+                code_lines.push(line);
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let item = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "));
+        if trimmed.starts_with("```") || trimmed.is_empty() || item.is_some() {
+            flush_paragraph(&mut html, &mut paragraph);
+        }
+        if item.is_none() {
+            flush_list(&mut html, &mut list);
+        }
+
+        if trimmed.starts_with("```") {
+            code = Some(Vec::new());
+        } else if let Some(item) = item {
+            list.push(item);
+        } else if !trimmed.is_empty() {
+            paragraph.push(trimmed);
+        }
+    }
+
+    flush_paragraph(&mut html, &mut paragraph);
+    flush_list(&mut html, &mut list);
+    if let Some(code_lines) = code {
+        html.push_str(&format!(
+            "<pre><code>{}</code></pre>",
+            escape_html(&code_lines.join("\n"))
+        ));
+    }
+    html
+}
+
+fn flush_paragraph(html: &mut String, lines: &mut Vec<&str>) {
+    if lines.is_empty() {
+        return;
+    }
+    let rendered: Vec<String> = lines.iter().map(|line| render_inline(line)).collect();
+    html.push_str(&format!("<p>{}</p>", rendered.join("<br>")));
+    lines.clear();
+}
+
+fn flush_list(html: &mut String, items: &mut Vec<&str>) {
+    if items.is_empty() {
+        return;
+    }
+    html.push_str("<ul>");
+    for item in items.iter() {
+        html.push_str(&format!("<li>{}</li>", render_inline(item)));
+    }
+    html.push_str("</ul>");
+    items.clear();
+}
+
+fn render_inline(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some((inner, after)) = delimited(rest, "`", "`") {
+            html.push_str(&format!("<code>{}</code>", escape_html(inner)));
+            rest = after;
+        } else if let Some((inner, after)) = delimited(rest, "**", "**") {
+            html.push_str(&format!("<strong>{}</strong>", render_inline(inner)));
+            rest = after;
+        } else if let Some((inner, after)) = delimited(rest, "*", "*") {
+            html.push_str(&format!("<em>{}</em>", render_inline(inner)));
+            rest = after;
+        } else if let Some((label, url, after)) = link(rest) {
+            html.push_str(&format!(
+                "<a href=\"{}\" rel=\"nofollow noopener noreferrer\" target=\"_blank\">{}</a>",
+                escape_html(url),
+                render_inline(label)
+            ));
+            rest = after;
+        } else {
+            html.push_str(&escape_html(&rest[..c.len_utf8()]));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    html
+}
+
+/// `text` split into what `open` and `close` enclose at its start and what
+/// follows, when the enclosed part is not empty.
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<(&'a str, &'a str)> {
+    let inner = text.strip_prefix(open)?;
+    let end = inner.find(close)?;
+    if end == 0 {
+        return None;
+    }
+    Some((&inner[..end], &inner[end + close.len()..]))
+}
+
+fn link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, after) = delimited(text, "[", "]")?;
+    let (url, after) = delimited(after, "(", ")")?;
+    let url = url.trim();
+    let allowed = ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| url.to_ascii_lowercase().starts_with(scheme));
+    allowed.then_some((label, url, after))
+}
+
+#[derive(Debug)]
+pub enum CommentError {
+    InfraError(InfraError),
+    NotFound,
+    /// Only the author may change or remove a comment.
+    Forbidden,
+    BadRequest(String),
+}
+
+impl From<InfraError> for CommentError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => CommentError::NotFound,
+            InfraError::Invalid(message) => CommentError::BadRequest(message),
+            _ => CommentError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for CommentError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("CommentModel with id has not been found"),
+            ),
+            Self::Forbidden => (
+                axum::http::StatusCode::FORBIDDEN,
+                String::from("Only the author can change a comment"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"CommentModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REL: &str = "rel=\"nofollow noopener noreferrer\" target=\"_blank\"";
+
+    /// Every `href` of `html` with the quotes around it checked, so an
+    /// attribute cannot be closed early.
+    fn hrefs(html: &str) -> Vec<&str> {
+        html.split("href=\"")
+            .skip(1)
+            .map(|rest| {
+                let (href, after) = rest.split_once('"').unwrap();
+                assert!(after.starts_with(" rel="), "{} breaks out of href", html);
+                href
+            })
+            .collect()
+    }
+
+    #[test]
+    fn renders_the_markdown_subset() {
+        assert_eq!(
+            render_markdown("Hello **bold** and *it*\nnext `line`\n\n- one\n* [two](https://example.com)\n```\nlet x = 1;\n```"),
+            format!(
+                "<p>Hello <strong>bold</strong> and <em>it</em><br>next <code>line</code></p>\
+                 <ul><li>one</li><li><a href=\"https://example.com\" {}>two</a></li></ul>\
+                 <pre><code>let x = 1;</code></pre>",
+                REL
+            )
+        );
+        assert_eq!(render_markdown(""), "");
+        assert_eq!(render_markdown("\n  \n"), "");
+    }
+
+    #[test]
+    fn escapes_html_in_text_code_and_link_labels() {
+        assert_eq!(
+            render_markdown("<script>alert('x')</script> & \"y\""),
+            "<p>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; &quot;y&quot;</p>"
+        );
+        assert_eq!(
+            render_markdown("`<script>` **<b>** *<i>*"),
+            "<p><code>&lt;script&gt;</code> <strong>&lt;b&gt;</strong> <em>&lt;i&gt;</em></p>"
+        );
+        assert_eq!(
+            render_markdown("```\n<script>alert(1)</script>\n```"),
+            "<pre><code>&lt;script&gt;alert(1)&lt;/script&gt;</code></pre>"
+        );
+        assert_eq!(
+            render_markdown("- <img src=x onerror=alert(1)>"),
+            "<ul><li>&lt;img src=x onerror=alert(1)&gt;</li></ul>"
+        );
+        assert_eq!(
+            render_markdown("[<script>alert(1)</script>](https://example.com)"),
+            format!(
+                "<p><a href=\"https://example.com\" {}>&lt;script&gt;alert(1)&lt;/script&gt;</a></p>",
+                REL
+            )
+        );
+        assert_eq!(
+            render_markdown("[`<b>`](https://example.com)"),
+            format!(
+                "<p><a href=\"https://example.com\" {}><code>&lt;b&gt;</code></a></p>",
+                REL
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_only_http_https_and_mailto_links() {
+        for body in [
+            "[x](https://example.com)",
+            "[x](HTTP://example.com)",
+            "[x]( mailto:ops@example.com )",
+        ] {
+            assert_eq!(hrefs(&render_markdown(body)).len(), 1, "{}", body);
+        }
+        for body in [
+            "[x](javascript:alert(1))",
+            "[x](JavaScript:alert(1))",
+            "[x]( javascript:alert(1))",
+            "[x](data:text/html;base64,PHNjcmlwdD4=)",
+            "[x](vbscript:msgbox)",
+            "[x](//example.com)",
+            "[x](/relative)",
+        ] {
+            let html = render_markdown(body);
+            assert!(hrefs(&html).is_empty(), "{} linked: {}", body, html);
+            assert!(!html.contains("<a"), "{}", html);
+        }
+    }
+
+    #[test]
+    fn escapes_quotes_in_hrefs() {
+        let html = render_markdown("[x](https://example.com/\"onmouseover=\"alert(1))");
+        assert_eq!(
+            hrefs(&html),
+            vec!["https://example.com/&quot;onmouseover=&quot;alert(1"]
+        );
+        let html = render_markdown("[x](https://example.com/'><script>)");
+        assert_eq!(
+            hrefs(&html),
+            vec!["https://example.com/&#39;&gt;&lt;script&gt;"]
+        );
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn nested_and_unterminated_markers_stay_text() {
+        assert_eq!(
+            render_markdown("**bold *it* x**"),
+            "<p><strong>bold <em>it</em> x</strong></p>"
+        );
+        assert_eq!(
+            render_markdown("*it `code` x*"),
+            "<p><em>it <code>code</code> x</em></p>"
+        );
+        assert_eq!(
+            render_markdown("`**not bold**`"),
+            "<p><code>**not bold**</code></p>"
+        );
+        assert_eq!(render_markdown("a * b"), "<p>a * b</p>");
+        assert_eq!(render_markdown("**open"), "<p>**open</p>");
+        assert_eq!(render_markdown("`open <b>"), "<p>`open &lt;b&gt;</p>");
+        assert_eq!(render_markdown("``"), "<p>``</p>");
+        assert_eq!(
+            render_markdown("[open](https://example.com"),
+            "<p>[open](https://example.com</p>"
+        );
+        assert_eq!(render_markdown("[no url]"), "<p>[no url]</p>");
+        assert_eq!(
+            render_markdown("[](https://example.com)"),
+            "<p>[](https://example.com)</p>"
+        );
+        assert_eq!(
+            render_markdown("[[x](https://example.com)"),
+            format!("<p><a href=\"https://example.com\" {}>[x</a></p>", REL)
+        );
+        // An unterminated fence takes the rest of the comment as code.
+        assert_eq!(
+            render_markdown("text\n```\n<b>\n\n*x*"),
+            "<p>text</p><pre><code>&lt;b&gt;\n\n*x*</code></pre>"
+        );
+    }
+
+    #[test]
+    fn hostile_input_never_produces_markup() {
+        let pieces = [
+            "<script>",
+            "*",
+            "**",
+            "`",
+            "[",
+            "]",
+            "(",
+            ")",
+            "\"",
+            "'",
+            "javascript:",
+            "data:",
+            "https://e.com",
+            "\n",
+            "- ",
+            "```",
+            "&",
+            "x",
+        ];
+        // Every ordered triple of pieces, which covers the nesting and
+        // unterminated cases the parser branches on.
+        for a in pieces {
+            for b in pieces {
+                for c in pieces {
+                    let body = format!("{}{}{}{}{}", a, b, c, b, a);
+                    let html = render_markdown(&body);
+                    assert!(!html.contains("<script"), "{:?} gave {}", body, html);
+                    for href in hrefs(&html) {
+                        assert!(href.starts_with("https://"), "{:?} gave {}", body, html);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::{IpAddr, SocketAddr};
+
+use ipnetwork::IpNetwork;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use super::metadata::Metadata;
+use super::server::ServerModel;
+
+/// A server as seen by configuration management tools: a single address and a
+/// host name that is unique within the inventory.
+#[derive(Debug, Clone)]
+pub struct InventoryHost {
+    pub host_name: String,
+    pub address: IpAddr,
+    pub server: ServerModel,
+}
+
+/// Keeps the servers that stand for a single host, servers registered with a
+/// subnet have no address to connect to. Names are made safe for inventory
+/// files, and names shared by several servers get the start of the id appended.
+pub fn inventory_hosts(servers: Vec<ServerModel>) -> Vec<InventoryHost> {
+    let servers: Vec<ServerModel> = servers
+        .into_iter()
+        .filter(|server| server.ip.prefix() == IpNetwork::from(server.ip.ip()).prefix())
+        .collect();
+
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for server in &servers {
+        *name_counts
+            .entry(sanitize_host_name(&server.name))
+            .or_default() += 1;
+    }
+
+    servers
+        .into_iter()
+        .map(|server| {
+            let name = sanitize_host_name(&server.name);
+            let host_name = if name_counts[&name] > 1 {
+                format!("{}-{}", name, &server.id[..8])
+            } else {
+                name
+            };
+            InventoryHost {
+                host_name,
+                address: server.ip.ip(),
+                server,
+            }
+        })
+        .collect()
+}
+
+fn sanitize_host_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        String::from("server")
+    } else {
+        name
+    }
+}
+
+/// Ansible group names only allow letters, digits and underscores.
+fn ansible_group_name(parts: &[&str]) -> String {
+    parts
+        .join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Groups derived from labels: every `key=value` label puts the host in
+/// `<key>_<value>`, and that group is a child of `<key>`.
+fn ansible_groups(
+    hosts: &[InventoryHost],
+) -> BTreeMap<String, (BTreeSet<String>, BTreeSet<String>)> {
+    let mut groups: BTreeMap<String, (BTreeSet<String>, BTreeSet<String>)> = BTreeMap::new();
+    for host in hosts {
+        for (key, value) in &host.server.labels {
+            let parent = ansible_group_name(&[key]);
+            let group = ansible_group_name(&[key, value]);
+            groups
+                .entry(group.clone())
+                .or_default()
+                .0
+                .insert(host.host_name.clone());
+            groups.entry(parent).or_default().1.insert(group);
+        }
+    }
+    groups
+}
+
+fn ungrouped_hosts(hosts: &[InventoryHost]) -> Vec<String> {
+    hosts
+        .iter()
+        .filter(|host| host.server.labels.is_empty())
+        .map(|host| host.host_name.clone())
+        .collect()
+}
+
+/// The JSON an Ansible inventory script prints for `--list`, with host
+/// variables inlined under `_meta.hostvars`.
+pub fn ansible_inventory(hosts: &[InventoryHost]) -> Value {
+    let groups = ansible_groups(hosts);
+
+    let mut inventory = Map::new();
+    let hostvars: Map<String, Value> = hosts
+        .iter()
+        .map(|host| {
+            (
+                host.host_name.clone(),
+                json!({
+                    "ansible_host": host.address.to_string(),
+                    "server_id": host.server.id,
+                    "server_name": host.server.name,
+                    "labels": host.server.labels,
+                }),
+            )
+        })
+        .collect();
+    inventory.insert(String::from("_meta"), json!({ "hostvars": hostvars }));
+
+    let mut top_level: Vec<String> = groups
+        .keys()
+        .filter(|name| {
+            !groups
+                .values()
+                .any(|(_, children)| children.contains(*name))
+        })
+        .cloned()
+        .collect();
+    top_level.push(String::from("ungrouped"));
+    inventory.insert(String::from("all"), json!({ "children": top_level }));
+    inventory.insert(
+        String::from("ungrouped"),
+        json!({ "hosts": ungrouped_hosts(hosts) }),
+    );
+
+    for (name, (group_hosts, children)) in groups {
+        let mut group = Map::new();
+        if !group_hosts.is_empty() {
+            group.insert(String::from("hosts"), json!(group_hosts));
+        }
+        if !children.is_empty() {
+            group.insert(String::from("children"), json!(children));
+        }
+        inventory.insert(name, Value::Object(group));
+    }
+
+    Value::Object(inventory)
+}
+
+/// The same inventory in Ansible's INI format. Every host line carries the
+/// connection variables; labels are only expressed through group membership.
+pub fn ansible_inventory_ini(hosts: &[InventoryHost]) -> String {
+    let host_line = |host: &InventoryHost| {
+        format!(
+            "{} ansible_host={} server_id={}\n",
+            host.host_name, host.address, host.server.id
+        )
+    };
+    let by_name: HashMap<&str, &InventoryHost> = hosts
+        .iter()
+        .map(|host| (host.host_name.as_str(), host))
+        .collect();
+
+    let mut ini = String::from("[ungrouped]\n");
+    for host in hosts.iter().filter(|host| host.server.labels.is_empty()) {
+        ini.push_str(&host_line(host));
+    }
+
+    for (name, (group_hosts, children)) in ansible_groups(hosts) {
+        if !group_hosts.is_empty() {
+            ini.push_str(&format!("\n[{}]\n", name));
+            for host_name in group_hosts {
+                ini.push_str(&host_line(by_name[host_name.as_str()]));
+            }
+        }
+        if !children.is_empty() {
+            ini.push_str(&format!("\n[{}:children]\n", name));
+            for child in children {
+                ini.push_str(&child);
+                ini.push('\n');
+            }
+        }
+    }
+
+    ini
+}
+
+/// Metadata keys that carry per-server OpenSSH settings.
+pub const SSH_USER_KEY: &str = "ssh.user";
+/// A number or a string holding one.
+pub const SSH_PORT_KEY: &str = "ssh.port";
+/// Either the name of another server, used through its alias, or a host name.
+pub const SSH_JUMP_KEY: &str = "ssh.jump";
+
+/// An `~/.ssh/config` fragment with one `Host` block per host.
+pub fn ssh_config(hosts: &[InventoryHost]) -> String {
+    let aliases: HashMap<&str, &str> = hosts
+        .iter()
+        .map(|host| (host.server.name.as_str(), host.host_name.as_str()))
+        .collect();
+
+    let mut config = format!(
+        "# Generated from the server inventory, {} hosts. Changes will be overwritten.\n",
+        hosts.len()
+    );
+    for host in hosts {
+        let metadata = &host.server.metadata;
+        config.push_str(&format!("\nHost {}\n", host.host_name));
+        config.push_str(&format!("    HostName {}\n", host.address));
+        if let Some(user) = ssh_setting(metadata, SSH_USER_KEY) {
+            config.push_str(&format!("    User {}\n", user));
+        }
+        if let Some(port) =
+            ssh_setting(metadata, SSH_PORT_KEY).and_then(|port| port.parse::<u16>().ok())
+        {
+            config.push_str(&format!("    Port {}\n", port));
+        }
+        if let Some(jump) = ssh_setting(metadata, SSH_JUMP_KEY) {
+            let jump = aliases.get(jump.as_str()).copied().unwrap_or(&jump);
+            config.push_str(&format!("    ProxyJump {}\n", jump));
+        }
+    }
+
+    config
+}
+
+/// A string or number metadata value, blank and unsafe ones left out since
+/// they would break the surrounding config.
+fn ssh_setting(metadata: &Metadata, key: &str) -> Option<String> {
+    let value = match metadata.get(key)? {
+        Value::String(value) => value.trim().to_string(),
+        Value::Number(value) => value.to_string(),
+        _ => return None,
+    };
+    (!value.is_empty() && !value.contains(char::is_whitespace)).then_some(value)
+}
+
+/// One entry of a Prometheus `http_sd_config` response.
+#[derive(Debug, Serialize)]
+pub struct PrometheusTargetGroup {
+    pub targets: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+/// One target group per host, scraping every port in `ports`. Server labels are
+/// passed on with their names reduced to what Prometheus accepts; ones that
+/// would shadow `job`, `instance` or the server labels below are dropped.
+pub fn prometheus_targets(hosts: &[InventoryHost], ports: &[u16]) -> Vec<PrometheusTargetGroup> {
+    const RESERVED: [&str; 4] = ["job", "instance", "server_name", "server_id"];
+
+    hosts
+        .iter()
+        .map(|host| {
+            let mut labels: BTreeMap<String, String> = host
+                .server
+                .labels
+                .iter()
+                .map(|(key, value)| (prometheus_label_name(key), value.clone()))
+                .filter(|(name, _)| !name.starts_with("__") && !RESERVED.contains(&name.as_str()))
+                .collect();
+            labels.insert(String::from("server_name"), host.server.name.clone());
+            labels.insert(String::from("server_id"), host.server.id.clone());
+
+            PrometheusTargetGroup {
+                targets: ports
+                    .iter()
+                    .map(|port| SocketAddr::new(host.address, *port).to_string())
+                    .collect(),
+                labels,
+            }
+        })
+        .collect()
+}
+
+fn prometheus_label_name(key: &str) -> String {
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer};
+
+pub type Labels = BTreeMap<String, String>;
+
+/// One clause of a label selector, following the Kubernetes selector syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelRequirement {
+    /// `key=value` or `key==value`
+    Equals(String, String),
+    /// `key!=value`, also matches servers without `key`
+    NotEquals(String, String),
+    /// `key in (a,b)`
+    In(String, Vec<String>),
+    /// `key notin (a,b)`, also matches servers without `key`
+    NotIn(String, Vec<String>),
+    /// `key`
+    Exists(String),
+    /// `!key`
+    NotExists(String),
+}
+
+/// Comma separated requirements that must all hold, e.g.
+/// `env=prod,role!=cache,team in (a,b)`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LabelSelector {
+    pub requirements: Vec<LabelRequirement>,
+}
+
+pub fn is_valid_label_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 253
+        && key.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+pub fn is_valid_label_value(value: &str) -> bool {
+    value.len() <= 63
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+pub fn validate_labels(labels: &Labels) -> Result<(), String> {
+    for (key, value) in labels {
+        check_key(key)?;
+        check_value(key, value)?;
+    }
+    Ok(())
+}
+
+/// Parses the `env=prod,role=db` shorthand used by plain html forms.
+pub fn parse_labels(value: &str) -> Result<Labels, String> {
+    let mut labels = Labels::new();
+    for pair in value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+    {
+        let (key, value) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` is not a `key=value` label", pair))?;
+        labels.insert(key.trim().to_string(), value.trim().to_string());
+    }
+    validate_labels(&labels)?;
+    Ok(labels)
+}
+
+/// Renders labels in the same `env=prod,role=db` shorthand `parse_labels` reads.
+pub fn format_labels(labels: &Labels) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl LabelRequirement {
+    pub fn matches(&self, labels: &Labels) -> bool {
+        match self {
+            LabelRequirement::Equals(key, value) => labels.get(key) == Some(value),
+            LabelRequirement::NotEquals(key, value) => labels.get(key) != Some(value),
+            LabelRequirement::In(key, values) => {
+                labels.get(key).is_some_and(|value| values.contains(value))
+            }
+            LabelRequirement::NotIn(key, values) => {
+                labels.get(key).is_none_or(|value| !values.contains(value))
+            }
+            LabelRequirement::Exists(key) => labels.contains_key(key),
+            LabelRequirement::NotExists(key) => !labels.contains_key(key),
+        }
+    }
+}
+
+impl LabelSelector {
+    /// The same semantics as the database filter; an empty selector matches everything.
+    pub fn matches(&self, labels: &Labels) -> bool {
+        self.requirements
+            .iter()
+            .all(|requirement| requirement.matches(labels))
+    }
+}
+
+impl FromStr for LabelSelector {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let requirements = split_top_level(value)?
+            .into_iter()
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .map(parse_requirement)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LabelSelector { requirements })
+    }
+}
+
+/// Splits on commas that are not inside an `in (...)` value list.
+fn split_top_level(value: &str) -> Result<Vec<&str>, String> {
+    let mut terms = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Err(String::from("unbalanced `)` in label selector")),
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(&value[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(String::from("unbalanced `(` in label selector"));
+    }
+    terms.push(&value[start..]);
+    Ok(terms)
+}
+
+fn parse_requirement(term: &str) -> Result<LabelRequirement, String> {
+    if let Some((head, rest)) = term.split_once('(') {
+        let values = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("`{}` must end with `)`", term))?;
+        if values.trim().is_empty() {
+            return Err(format!("`{}` needs at least one value", term));
+        }
+        let values = values
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .collect::<Vec<_>>();
+        let mut words = head.split_whitespace();
+        let (key, operator) = match (words.next(), words.next(), words.next()) {
+            (Some(key), Some(operator), None) => (key.to_string(), operator),
+            _ => return Err(format!("`{}` is not a valid set requirement", term)),
+        };
+        check_key(&key)?;
+        for value in &values {
+            check_value(&key, value)?;
+        }
+        return match operator {
+            "in" => Ok(LabelRequirement::In(key, values)),
+            "notin" => Ok(LabelRequirement::NotIn(key, values)),
+            _ => Err(format!("unknown label selector operator `{}`", operator)),
+        };
+    }
+
+    if let Some(key) = term.strip_prefix('!') {
+        let key = key.trim().to_string();
+        check_key(&key)?;
+        return Ok(LabelRequirement::NotExists(key));
+    }
+
+    let (key, value, requirement): (&str, &str, fn(String, String) -> LabelRequirement) =
+        if let Some((key, value)) = term.split_once("!=") {
+            (key, value, LabelRequirement::NotEquals)
+        } else if let Some((key, value)) = term.split_once("==") {
+            (key, value, LabelRequirement::Equals)
+        } else if let Some((key, value)) = term.split_once('=') {
+            (key, value, LabelRequirement::Equals)
+        } else {
+            let key = term.to_string();
+            check_key(&key)?;
+            return Ok(LabelRequirement::Exists(key));
+        };
+
+    let (key, value) = (key.trim().to_string(), value.trim().to_string());
+    check_key(&key)?;
+    check_value(&key, &value)?;
+    Ok(requirement(key, value))
+}
+
+pub fn check_key(key: &str) -> Result<(), String> {
+    if is_valid_label_key(key) {
+        Ok(())
+    } else {
+        Err(format!("`{}` is not a valid label key", key))
+    }
+}
+
+pub fn check_value(key: &str, value: &str) -> Result<(), String> {
+    if is_valid_label_value(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{}` is not a valid value for label `{}`",
+            value, key
+        ))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LabelsInput {
+    Map(Labels),
+    Text(String),
+}
+
+/// Accepts either a JSON object of string values or the `env=prod,role=db` shorthand.
+pub fn deserialize_labels<'de, D>(deserializer: D) -> Result<Labels, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match LabelsInput::deserialize(deserializer)? {
+        LabelsInput::Map(labels) => validate_labels(&labels)
+            .map(|_| labels)
+            .map_err(de::Error::custom),
+        LabelsInput::Text(value) => parse_labels(&value).map_err(de::Error::custom),
+    }
+}
+
+pub fn deserialize_optional_labels<'de, D>(deserializer: D) -> Result<Option<Labels>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_labels(deserializer).map(Some)
+}
+
+pub fn deserialize_optional_label_selector<'de, D>(
+    deserializer: D,
+) -> Result<Option<LabelSelector>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => value.parse().map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<Vec<LabelRequirement>, String> {
+        value
+            .parse::<LabelSelector>()
+            .map(|selector| selector.requirements)
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_every_operator() {
+        assert_eq!(
+            parse("env=prod,tier==web,role!=cache,team in (a,b),zone notin (x),backup,!legacy"),
+            Ok(vec![
+                LabelRequirement::Equals("env".into(), "prod".into()),
+                LabelRequirement::Equals("tier".into(), "web".into()),
+                LabelRequirement::NotEquals("role".into(), "cache".into()),
+                LabelRequirement::In("team".into(), strings(&["a", "b"])),
+                LabelRequirement::NotIn("zone".into(), strings(&["x"])),
+                LabelRequirement::Exists("backup".into()),
+                LabelRequirement::NotExists("legacy".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn ignores_whitespace_and_empty_terms() {
+        assert_eq!(
+            parse("  env = prod , , team  in ( a , b ) ,! legacy "),
+            Ok(vec![
+                LabelRequirement::Equals("env".into(), "prod".into()),
+                LabelRequirement::In("team".into(), strings(&["a", "b"])),
+                LabelRequirement::NotExists("legacy".into()),
+            ])
+        );
+        assert_eq!(parse(""), Ok(vec![]));
+        assert_eq!(parse(" , "), Ok(vec![]));
+    }
+
+    #[test]
+    fn accepts_an_empty_value_and_prefixed_keys() {
+        assert_eq!(
+            parse("example.com/owner=,ssh/user=root"),
+            Ok(vec![
+                LabelRequirement::Equals("example.com/owner".into(), "".into()),
+                LabelRequirement::Equals("ssh/user".into(), "root".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_selectors() {
+        for selector in [
+            "team in (a,b",
+            "team in a,b)",
+            "team in ()",
+            "team notin ( )",
+            "team (a)",
+            "team maybe (a)",
+            "team in (a) extra",
+            "a b in (c)",
+            "=prod",
+            "!",
+            "-env=prod",
+            "env=prod value",
+            "env=a/b",
+        ] {
+            assert!(parse(selector).is_err(), "{} should not parse", selector);
+        }
+    }
+
+    #[test]
+    fn enforces_key_and_value_limits() {
+        let key = format!("k{}", "e".repeat(252));
+        assert!(parse(&key).is_ok());
+        assert!(parse(&format!("{}e", key)).is_err());
+
+        let value = "v".repeat(63);
+        assert!(parse(&format!("env={}", value)).is_ok());
+        assert!(parse(&format!("env={}v", value)).is_err());
+        assert!(parse(&format!("env in (a,{}v)", value)).is_err());
+    }
+
+    #[test]
+    fn negative_requirements_match_missing_labels() {
+        let labels = Labels::from([("env".to_string(), "prod".to_string())]);
+        let selector: LabelSelector = "role!=cache,team notin (a),!legacy".parse().unwrap();
+        assert!(selector.matches(&labels));
+
+        let selector: LabelSelector = "env in (dev,prod),env!=dev,env".parse().unwrap();
+        assert!(selector.matches(&labels));
+        let selector: LabelSelector = "env notin (prod)".parse().unwrap();
+        assert!(!selector.matches(&labels));
+    }
+
+    #[test]
+    fn parses_the_form_shorthand() {
+        assert_eq!(
+            parse_labels(" env=prod, role = db ,"),
+            Ok(Labels::from([
+                ("env".to_string(), "prod".to_string()),
+                ("role".to_string(), "db".to_string()),
+            ]))
+        );
+        assert!(parse_labels("env").is_err());
+        assert!(parse_labels("env=a b").is_err());
+    }
+}
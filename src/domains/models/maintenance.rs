@@ -0,0 +1,663 @@
+use std::fmt;
+use std::str::FromStr;
+
+use axum::response::IntoResponse;
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveDateTime, TimeDelta, Utc, Weekday};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+const MAX_INTERVAL: u32 = 1000;
+/// Upper bound on the days, weeks or months a recurring window is followed for.
+const MAX_PERIODS: u32 = 100_000;
+/// Longest a single occurrence may last.
+const MAX_WINDOW_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// The part of an iCalendar RRULE maintenance windows support, e.g.
+/// `FREQ=WEEKLY;INTERVAL=2;BYDAY=SA,SU;COUNT=10`. Times are in UTC.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    /// Repeats every `interval` days, weeks or months.
+    pub interval: u32,
+    /// Days a weekly rule repeats on; the day of the first occurrence when empty.
+    pub by_day: Vec<Weekday>,
+    /// Number of occurrences, the first one included.
+    pub count: Option<u32>,
+    /// No occurrence starts later than this.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value.strip_prefix("RRULE:").unwrap_or(value);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut count = None;
+        let mut until = None;
+        for part in value
+            .split(';')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let (name, part_value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("`{}` is not a `NAME=value` rule part", part))?;
+            match name.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match part_value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => {
+                            return Err(format!(
+                                "`FREQ={}` is not supported, use DAILY, WEEKLY or MONTHLY",
+                                part_value
+                            ))
+                        }
+                    })
+                }
+                "INTERVAL" => {
+                    interval = part_value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|interval| (1..=MAX_INTERVAL).contains(interval))
+                        .ok_or_else(|| {
+                            format!(
+                                "`INTERVAL={}` must be a number from 1 to {}",
+                                part_value, MAX_INTERVAL
+                            )
+                        })?
+                }
+                "BYDAY" => {
+                    by_day = part_value
+                        .split(',')
+                        .map(parse_weekday)
+                        .collect::<Result<Vec<_>, _>>()?
+                }
+                "COUNT" => {
+                    count = Some(
+                        part_value
+                            .parse::<u32>()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or_else(|| {
+                                format!("`COUNT={}` must be a positive number", part_value)
+                            })?,
+                    )
+                }
+                "UNTIL" => until = Some(parse_until(part_value)?),
+                _ => {
+                    return Err(format!(
+                        "`{}` is not supported, use FREQ, INTERVAL, BYDAY, COUNT or UNTIL",
+                        name
+                    ))
+                }
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| String::from("A recurrence needs a `FREQ`"))?;
+        if !by_day.is_empty() && frequency != Frequency::Weekly {
+            return Err(String::from("`BYDAY` is only supported with `FREQ=WEEKLY`"));
+        }
+        if count.is_some() && until.is_some() {
+            return Err(String::from("`COUNT` and `UNTIL` cannot be combined"));
+        }
+        by_day.sort_by_key(Weekday::num_days_from_monday);
+        by_day.dedup();
+
+        Ok(Recurrence {
+            frequency,
+            interval,
+            by_day,
+            count,
+            until,
+        })
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    let value = value.trim();
+    [
+        Weekday::Mon,
+        Weekday::Tue,
+        Weekday::Wed,
+        Weekday::Thu,
+        Weekday::Fri,
+        Weekday::Sat,
+        Weekday::Sun,
+    ]
+    .into_iter()
+    .find(|day| value.eq_ignore_ascii_case(weekday_code(*day)))
+    .ok_or_else(|| format!("`{}` is not a day, use MO, TU, WE, TH, FR, SA or SU", value))
+}
+
+/// Either the iCalendar `20240131T220000Z` form or RFC 3339.
+fn parse_until(value: &str) -> Result<DateTime<Utc>, String> {
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .map(|until| until.and_utc())
+        .or_else(|_| DateTime::parse_from_rfc3339(value).map(|until| until.to_utc()))
+        .map_err(|_| format!("`UNTIL={}` is not a UTC date and time", value))
+}
+
+impl Recurrence {
+    /// Starts in the `period`-th day, week or month counted from `first`,
+    /// before `COUNT` and `UNTIL` are applied. `None` once out of range.
+    fn period_starts(&self, first: DateTime<Utc>, period: u32) -> Option<Vec<DateTime<Utc>>> {
+        let step = u64::from(period.checked_mul(self.interval)?);
+        match self.frequency {
+            Frequency::Daily => Some(vec![first.checked_add_days(Days::new(step))?]),
+            Frequency::Weekly if self.by_day.is_empty() => {
+                Some(vec![first.checked_add_days(Days::new(step * 7))?])
+            }
+            Frequency::Weekly => {
+                let monday = first
+                    .checked_sub_days(Days::new(first.weekday().num_days_from_monday().into()))?
+                    .checked_add_days(Days::new(step * 7))?;
+                self.by_day
+                    .iter()
+                    .map(|day| {
+                        monday.checked_add_days(Days::new(day.num_days_from_monday().into()))
+                    })
+                    .collect()
+            }
+            Frequency::Monthly => {
+                let month0 = u64::from(first.month0()) + step;
+                let year = i32::try_from(i64::from(first.year()) + (month0 / 12) as i64).ok()?;
+                // Months without the day of the first occurrence are skipped, like RFC 5545 does.
+                Some(
+                    NaiveDate::from_ymd_opt(year, (month0 % 12) as u32 + 1, first.day())
+                        .map(|date| date.and_time(first.time()).and_utc())
+                        .into_iter()
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    /// A period such that every occurrence of the periods before it starts no
+    /// later than `at`, found from the elapsed days, weeks or months alone.
+    fn period_at(&self, first: DateTime<Utc>, at: DateTime<Utc>) -> u32 {
+        if at <= first {
+            return 0;
+        }
+        let units = match self.frequency {
+            Frequency::Daily => (at - first).num_days(),
+            Frequency::Weekly => (at - first).num_weeks(),
+            Frequency::Monthly => {
+                i64::from(at.year() - first.year()) * 12 + i64::from(at.month0())
+                    - i64::from(first.month0())
+            }
+        };
+        u32::try_from(units / i64::from(self.interval)).unwrap_or(u32::MAX)
+    }
+
+    /// Occurrences starting in the periods before `period`, the first one
+    /// included, for counting towards `COUNT` without walking them.
+    fn starts_before(&self, first: DateTime<Utc>, period: u32) -> u64 {
+        if period == 0 {
+            return 0;
+        }
+        let later_periods = u64::from(period - 1);
+        match self.frequency {
+            Frequency::Weekly if !self.by_day.is_empty() => {
+                let rest_of_first_week = self
+                    .period_starts(first, 0)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|start| *start > first)
+                    .count() as u64;
+                1 + rest_of_first_week + later_periods * self.by_day.len() as u64
+            }
+            Frequency::Monthly if first.day() > 28 => {
+                // Which months have the day repeats with the 400 year calendar cycle.
+                let cycle = 4800 / gcd(self.interval, 4800);
+                let with_day = |periods: u32| {
+                    (1..=periods)
+                        .filter(|period| {
+                            self.period_starts(first, *period)
+                                .is_some_and(|starts| !starts.is_empty())
+                        })
+                        .count() as u64
+                };
+                let full_cycles = later_periods / u64::from(cycle);
+                let full_cycles_with_day = if full_cycles > 0 {
+                    full_cycles * with_day(cycle)
+                } else {
+                    0
+                };
+                1 + full_cycles_with_day + with_day((later_periods % u64::from(cycle)) as u32)
+            }
+            _ => 1 + later_periods,
+        }
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn deserialize_optional_recurrence<'de, D>(
+    deserializer: D,
+) -> Result<Option<Recurrence>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if value.trim().is_empty() => Ok(None),
+        Some(value) => value.parse().map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// One stretch of time a window is in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Occurrence {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+/// Planned downtime of a server, either once or repeating.
+#[derive(Debug, Clone)]
+pub struct MaintenanceWindowModel {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    /// Start and end of the first occurrence; later ones last as long.
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub recurrence: Option<Recurrence>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MaintenanceWindowModel {
+    /// All occurrences in order, beginning with `starts_at` whether or not the
+    /// rule matches it, which is also counted towards `COUNT` as in RFC 5545.
+    /// Stops at occurrences that would end past the dates chrono represents.
+    /// Only those starting in `period` or later are returned, `COUNT` still
+    /// counted from the first one.
+    fn occurrences_from(&self, period: u32) -> Box<dyn Iterator<Item = Occurrence> + '_> {
+        let first = self.starts_at;
+        let duration = self.ends_at - self.starts_at;
+        let starts: Box<dyn Iterator<Item = DateTime<Utc>>> = match &self.recurrence {
+            None => Box::new(std::iter::once(first).filter(move |_| period == 0)),
+            Some(recurrence) => {
+                let remaining = recurrence.count.map_or(u64::MAX, |count| {
+                    u64::from(count).saturating_sub(recurrence.starts_before(first, period))
+                });
+                Box::new(
+                    std::iter::once(first)
+                        .filter(move |_| period == 0)
+                        .chain(
+                            (period..MAX_PERIODS)
+                                .map_while(move |period| recurrence.period_starts(first, period))
+                                .flatten()
+                                .filter(move |start| *start > first)
+                                .take_while(move |start| {
+                                    recurrence.until.is_none_or(|until| *start <= until)
+                                }),
+                        )
+                        .take(usize::try_from(remaining).unwrap_or(usize::MAX)),
+                )
+            }
+        };
+        Box::new(starts.map_while(move |starts_at| {
+            Some(Occurrence {
+                starts_at,
+                ends_at: starts_at.checked_add_signed(duration)?,
+            })
+        }))
+    }
+
+    /// Occurrences from the period of the earliest one that may still be in
+    /// effect at `at`, skipping the earlier periods without walking them.
+    fn occurrences_around(&self, at: DateTime<Utc>) -> Box<dyn Iterator<Item = Occurrence> + '_> {
+        let period = match (
+            &self.recurrence,
+            at.checked_sub_signed(self.ends_at - self.starts_at),
+        ) {
+            (Some(recurrence), Some(earliest_start)) => {
+                recurrence.period_at(self.starts_at, earliest_start)
+            }
+            _ => 0,
+        };
+        self.occurrences_from(period)
+    }
+
+    /// The occurrence in effect at `at`.
+    pub fn active_at(&self, at: DateTime<Utc>) -> Option<Occurrence> {
+        self.occurrences_around(at)
+            .take_while(|occurrence| occurrence.starts_at <= at)
+            .find(|occurrence| occurrence.ends_at > at)
+    }
+
+    /// The occurrence in effect at `at`, or else the next one to come.
+    pub fn next_at(&self, at: DateTime<Utc>) -> Option<Occurrence> {
+        self.occurrences_around(at)
+            .find(|occurrence| occurrence.ends_at > at)
+    }
+}
+
+/// Of the windows in effect at `at`, the occurrence that lasts longest.
+pub fn active_maintenance(
+    windows: &[MaintenanceWindowModel],
+    at: DateTime<Utc>,
+) -> Option<Occurrence> {
+    windows
+        .iter()
+        .filter_map(|window| window.active_at(at))
+        .max_by_key(|occurrence| occurrence.ends_at)
+}
+
+pub fn check_window(starts_at: DateTime<Utc>, ends_at: DateTime<Utc>) -> Result<(), String> {
+    if ends_at <= starts_at {
+        return Err(String::from(
+            "A maintenance window has to end after it starts",
+        ));
+    }
+    if ends_at - starts_at > TimeDelta::days(MAX_WINDOW_DAYS) {
+        return Err(format!(
+            "A maintenance window lasts at most {} days",
+            MAX_WINDOW_DAYS
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_reason(reason: &str) -> Result<(), String> {
+    if reason.len() > 1000 {
+        return Err(String::from("A reason is at most 1000 characters"));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum MaintenanceError {
+    InfraError(InfraError),
+    NotFound,
+    BadRequest(String),
+}
+
+impl From<InfraError> for MaintenanceError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => MaintenanceError::NotFound,
+            InfraError::Invalid(message) => MaintenanceError::BadRequest(message),
+            _ => MaintenanceError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for MaintenanceError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("MaintenanceWindowModel with id has not been found"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"MaintenanceWindowModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().to_utc()
+    }
+
+    /// A two hour window from `starts_at`, repeating by `rrule`.
+    fn window(starts_at: &str, rrule: &str) -> MaintenanceWindowModel {
+        let starts_at = at(starts_at);
+        MaintenanceWindowModel {
+            id: Uuid::nil(),
+            server_id: Uuid::nil(),
+            starts_at,
+            ends_at: starts_at + TimeDelta::hours(2),
+            recurrence: Some(rrule.parse().unwrap()),
+            reason: None,
+            created_at: starts_at,
+        }
+    }
+
+    fn starts(window: &MaintenanceWindowModel, n: usize) -> Vec<DateTime<Utc>> {
+        window
+            .occurrences_from(0)
+            .take(n)
+            .map(|occurrence| occurrence.starts_at)
+            .collect()
+    }
+
+    #[test]
+    fn daily_repeats_every_interval() {
+        let window = window("2024-07-01T02:00:00Z", "FREQ=DAILY;INTERVAL=2");
+        assert_eq!(
+            starts(&window, 3),
+            vec![
+                at("2024-07-01T02:00:00Z"),
+                at("2024-07-03T02:00:00Z"),
+                at("2024-07-05T02:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_by_day_follows_the_listed_days() {
+        // 2024-07-01 is a Monday.
+        let window = window("2024-07-01T02:00:00Z", "FREQ=WEEKLY;BYDAY=MO,TH");
+        assert_eq!(
+            starts(&window, 4),
+            vec![
+                at("2024-07-01T02:00:00Z"),
+                at("2024-07-04T02:00:00Z"),
+                at("2024-07-08T02:00:00Z"),
+                at("2024-07-11T02:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_by_day_keeps_a_first_occurrence_off_the_listed_days() {
+        // 2024-07-03 is a Wednesday.
+        let window = window("2024-07-03T02:00:00Z", "FREQ=WEEKLY;BYDAY=MO");
+        assert_eq!(
+            starts(&window, 3),
+            vec![
+                at("2024-07-03T02:00:00Z"),
+                at("2024-07-08T02:00:00Z"),
+                at("2024-07-15T02:00:00Z"),
+            ]
+        );
+        assert!(window.active_at(at("2024-07-03T03:00:00Z")).is_some());
+    }
+
+    #[test]
+    fn monthly_skips_months_without_the_day() {
+        let window = window("2024-01-31T02:00:00Z", "FREQ=MONTHLY");
+        assert_eq!(
+            starts(&window, 3),
+            vec![
+                at("2024-01-31T02:00:00Z"),
+                at("2024-03-31T02:00:00Z"),
+                at("2024-05-31T02:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn count_includes_the_first_occurrence() {
+        let window = window("2024-07-03T02:00:00Z", "FREQ=WEEKLY;BYDAY=MO;COUNT=2");
+        assert_eq!(
+            starts(&window, 10),
+            vec![at("2024-07-03T02:00:00Z"), at("2024-07-08T02:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn until_is_the_last_possible_start() {
+        let window = window("2024-07-01T02:00:00Z", "FREQ=DAILY;UNTIL=20240703T020000Z");
+        assert_eq!(
+            starts(&window, 10),
+            vec![
+                at("2024-07-01T02:00:00Z"),
+                at("2024-07-02T02:00:00Z"),
+                at("2024-07-03T02:00:00Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn occurrences_ending_out_of_range_stop_the_window() {
+        let mut window = window("2024-07-03T02:00:00Z", "FREQ=WEEKLY;BYDAY=TH");
+        window.ends_at = DateTime::<Utc>::MAX_UTC - TimeDelta::hours(1);
+        assert_eq!(window.occurrences_from(0).count(), 1);
+        assert_eq!(
+            window.next_at(at("2024-07-03T03:00:00Z")),
+            Some(Occurrence {
+                starts_at: window.starts_at,
+                ends_at: window.ends_at,
+            })
+        );
+    }
+
+    /// `active_at` and `next_at` by walking every occurrence from the first.
+    fn walked(
+        window: &MaintenanceWindowModel,
+        at: DateTime<Utc>,
+    ) -> (Option<Occurrence>, Option<Occurrence>) {
+        (
+            window
+                .occurrences_from(0)
+                .take_while(|occurrence| occurrence.starts_at <= at)
+                .find(|occurrence| occurrence.ends_at > at),
+            window
+                .occurrences_from(0)
+                .find(|occurrence| occurrence.ends_at > at),
+        )
+    }
+
+    #[test]
+    fn skipping_to_the_period_matches_walking() {
+        for (starts_at, rrule) in [
+            ("2024-07-01T02:00:00Z", "FREQ=DAILY"),
+            ("2024-07-01T23:00:00Z", "FREQ=DAILY;INTERVAL=3;COUNT=20"),
+            ("2024-07-03T02:00:00Z", "FREQ=WEEKLY;INTERVAL=2"),
+            (
+                "2024-07-03T02:00:00Z",
+                "FREQ=WEEKLY;BYDAY=MO,WE,SU;COUNT=17",
+            ),
+            ("2024-07-07T22:00:00Z", "FREQ=WEEKLY;INTERVAL=3;BYDAY=MO,TU"),
+            ("2024-01-31T02:00:00Z", "FREQ=MONTHLY;COUNT=9"),
+            ("2024-02-29T02:00:00Z", "FREQ=MONTHLY;INTERVAL=11;COUNT=6"),
+            (
+                "2024-07-15T02:00:00Z",
+                "FREQ=MONTHLY;INTERVAL=2;UNTIL=20250601T000000Z",
+            ),
+        ] {
+            let window = window(starts_at, rrule);
+            let mut at = window.starts_at - TimeDelta::days(3);
+            while at < window.starts_at + TimeDelta::days(2 * 366) {
+                assert_eq!(
+                    (window.active_at(at), window.next_at(at)),
+                    walked(&window, at),
+                    "{} at {}",
+                    rrule,
+                    at
+                );
+                at += TimeDelta::minutes(433);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_occurrences_far_from_the_first() {
+        let daily = window("2024-07-01T02:00:00Z", "FREQ=DAILY");
+        assert_eq!(
+            daily.active_at(at("2250-03-05T03:00:00Z")),
+            Some(Occurrence {
+                starts_at: at("2250-03-05T02:00:00Z"),
+                ends_at: at("2250-03-05T04:00:00Z"),
+            })
+        );
+
+        let monthly = window("2024-01-31T02:00:00Z", "FREQ=MONTHLY;COUNT=1000");
+        let last = monthly.occurrences_from(0).last().unwrap();
+        assert_eq!(monthly.next_at(last.starts_at), Some(last));
+        assert_eq!(monthly.next_at(last.ends_at), None);
+    }
+
+    #[test]
+    fn windows_are_bounded_in_length() {
+        let starts_at = at("2024-07-01T02:00:00Z");
+        assert!(check_window(starts_at, starts_at + TimeDelta::days(366)).is_ok());
+        assert!(check_window(starts_at, starts_at + TimeDelta::days(367)).is_err());
+        assert!(check_window(starts_at, starts_at).is_err());
+    }
+}
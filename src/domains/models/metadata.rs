@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use super::labels::{LabelSelector, Labels};
+use crate::infra::errors::InfraError;
+
+/// Free-form attributes of a server such as its rack or cost center.
+pub type Metadata = Map<String, Value>;
+
+/// Query parameters starting with this select servers by metadata, e.g. `meta.rack=R12`.
+pub const METADATA_FILTER_PREFIX: &str = "meta.";
+
+/// A JSON Schema the metadata of every server matched by `label_selector` must
+/// satisfy, e.g. one schema for `env=prod` and another one for `env=dev`.
+#[derive(Debug, Clone)]
+pub struct MetadataSchemaModel {
+    pub id: Uuid,
+    pub name: String,
+    pub label_selector: LabelSelector,
+    /// The selector as it was written, kept for display.
+    pub label_selector_text: String,
+    pub schema: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Rejects documents that are not valid JSON Schemas.
+pub fn check_schema(schema: &Value) -> Result<(), String> {
+    jsonschema::validator_for(schema)
+        .map(|_| ())
+        .map_err(|err| format!("Not a valid JSON Schema: {}", err))
+}
+
+/// Checks `metadata` against every schema whose selector matches `labels`,
+/// reporting all violations at once.
+pub fn validate_metadata(
+    schemas: &[MetadataSchemaModel],
+    labels: &Labels,
+    metadata: &Metadata,
+) -> Result<(), String> {
+    let instance = Value::Object(metadata.clone());
+    let mut violations = Vec::new();
+    for schema in schemas
+        .iter()
+        .filter(|schema| schema.label_selector.matches(labels))
+    {
+        let validator = jsonschema::validator_for(&schema.schema)
+            .map_err(|err| format!("Metadata schema `{}` is broken: {}", schema.name, err))?;
+        for error in validator.iter_errors(&instance) {
+            let path = error.instance_path.to_string();
+            violations.push(format!(
+                "{}: {} (schema `{}`)",
+                if path.is_empty() { "/" } else { &path },
+                error,
+                schema.name
+            ));
+        }
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid metadata: {}", violations.join("; ")))
+    }
+}
+
+/// `meta.hw.cpus=8` matches servers whose `metadata.hw.cpus` renders as `8`;
+/// strings compare without quotes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataRequirement {
+    pub path: Vec<String>,
+    pub value: String,
+}
+
+/// Picks the `meta.` parameters out of a query string; other parameters are
+/// left to the extractors that own them.
+pub fn deserialize_metadata_filters<'de, D>(
+    deserializer: D,
+) -> Result<Vec<MetadataRequirement>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let params = HashMap::<String, String>::deserialize(deserializer)?;
+    let mut requirements = params
+        .into_iter()
+        .filter_map(|(key, value)| {
+            key.strip_prefix(METADATA_FILTER_PREFIX)
+                .map(|path| (path.to_string(), value))
+        })
+        .map(|(path, value)| {
+            let path: Vec<String> = path.split('.').map(str::to_string).collect();
+            if path.iter().any(String::is_empty) {
+                return Err(de::Error::custom(format!(
+                    "`{}{}` is not a valid metadata path",
+                    METADATA_FILTER_PREFIX,
+                    path.join(".")
+                )));
+            }
+            Ok(MetadataRequirement { path, value })
+        })
+        .collect::<Result<Vec<_>, D::Error>>()?;
+    requirements.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(requirements)
+}
+
+#[derive(Debug)]
+pub enum MetadataSchemaError {
+    InfraError(InfraError),
+    NotFound,
+    /// Another schema already has the name.
+    Conflict,
+    BadRequest(String),
+}
+
+impl From<InfraError> for MetadataSchemaError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => MetadataSchemaError::NotFound,
+            InfraError::UniqueViolation(_) => MetadataSchemaError::Conflict,
+            InfraError::Invalid(message) => MetadataSchemaError::BadRequest(message),
+            _ => MetadataSchemaError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for MetadataSchemaError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("MetadataSchemaModel with id has not been found"),
+            ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("A metadata schema with this name already exists"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"MetadataSchemaModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
@@ -1,2 +1,18 @@
+pub mod api_key;
+pub mod comment;
+pub mod inventory;
+pub mod labels;
+pub mod maintenance;
+pub mod metadata;
+pub mod saved_view;
+pub mod secret;
 pub mod server;
-pub mod users;
\ No newline at end of file
+pub mod server_check;
+pub mod server_csv;
+pub mod server_dependency;
+pub mod server_dns;
+pub mod server_history;
+pub mod service;
+pub mod subnet;
+pub mod uptime;
+pub mod users;
@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+/// A named `list_servers` filter, kept as the query parameters it stands for.
+#[derive(Debug, Clone)]
+pub struct SavedViewModel {
+    pub id: Uuid,
+    pub name: String,
+    pub filter: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn check_view_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() || name.len() > 255 {
+        return Err(String::from("A view name takes 1 to 255 characters"));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum ViewError {
+    InfraError(InfraError),
+    NotFound,
+    /// The owner already has a view with the same name.
+    Conflict,
+    BadRequest(String),
+}
+
+impl From<InfraError> for ViewError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => ViewError::NotFound,
+            InfraError::UniqueViolation(_) => ViewError::Conflict,
+            InfraError::Invalid(message) => ViewError::BadRequest(message),
+            _ => ViewError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for ViewError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("SavedViewModel with id has not been found"),
+            ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("A view with this name already exists"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"SavedViewModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
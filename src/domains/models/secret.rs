@@ -0,0 +1,111 @@
+use axum::response::IntoResponse;
+use chrono::{DateTime, TimeDelta, Utc};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+/// What is known about a secret without opening it. The value itself only
+/// leaves the vault through a reveal.
+#[derive(Debug, Clone)]
+pub struct SecretModel {
+    pub server_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Names are part of the URL, e.g. `root-password` or `ssh.deploy_key`.
+pub fn check_secret_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 255
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "`{}` is not a valid secret name, use 1 to 255 letters, digits, `-`, `_` or `.`",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Large enough for private keys and certificate chains.
+pub fn check_secret_value(value: &str) -> Result<(), String> {
+    if value.is_empty() || value.len() > 65536 {
+        return Err(String::from("A secret value takes 1 byte to 64 KiB"));
+    }
+    Ok(())
+}
+
+/// The audit entry for `server_id`/`name`, also the associated data the value
+/// is sealed with.
+pub fn secret_entry(server_id: Uuid, name: &str) -> String {
+    format!("{}/{}", server_id, name)
+}
+
+/// Failed reveals a user may make within `REVEAL_LOCKOUT` before further
+/// attempts are refused without checking the password.
+pub const MAX_FAILED_REVEALS: i64 = 5;
+pub const REVEAL_LOCKOUT: TimeDelta = TimeDelta::minutes(15);
+
+#[derive(Debug)]
+pub enum SecretError {
+    InfraError(InfraError),
+    NotFound,
+    BadRequest(String),
+    /// The password given to reveal a secret is wrong.
+    Unauthorized,
+    /// Too many wrong passwords were given for reveals lately.
+    TooManyAttempts,
+    /// No `VAULT_MASTER_KEY` is configured.
+    VaultDisabled,
+}
+
+impl From<InfraError> for SecretError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => SecretError::NotFound,
+            InfraError::Invalid(message) => SecretError::BadRequest(message),
+            _ => SecretError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for SecretError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("SecretModel has not been found"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::Unauthorized => (
+                axum::http::StatusCode::UNAUTHORIZED,
+                String::from("The password is not correct"),
+            ),
+            Self::TooManyAttempts => (
+                axum::http::StatusCode::TOO_MANY_REQUESTS,
+                format!(
+                    "Too many wrong passwords, try again in {} minutes",
+                    REVEAL_LOCKOUT.num_minutes()
+                ),
+            ),
+            Self::VaultDisabled => (
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                String::from("The vault is not configured, set VAULT_MASTER_KEY"),
+            ),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"SecretModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
@@ -1,19 +1,198 @@
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+
 use axum::response::IntoResponse;
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use super::labels::Labels;
+use super::maintenance::Occurrence;
+use super::metadata::Metadata;
+use super::server_check::ServerCheckModel;
+use super::service::ServiceModel;
 use crate::infra::errors::InfraError;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerModel {
     pub id: String,
     pub name: String,
-    pub ip: String,
+    #[serde(
+        serialize_with = "serialize_server_address",
+        deserialize_with = "deserialize_server_address"
+    )]
+    pub ip: IpNetwork,
+    pub version: i32,
+    pub labels: Labels,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Ports the health checker connects to, empty for the configured defaults.
+    #[serde(default)]
+    pub check_ports: Vec<u16>,
+    #[serde(default)]
+    pub metadata: Metadata,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    /// Latest health check, only loaded by listings and never part of history.
+    #[serde(skip)]
+    pub check: Option<ServerCheckModel>,
+    /// Only loaded when a listing asks for `include=services`.
+    #[serde(skip)]
+    pub services: Option<Vec<ServiceModel>>,
+    /// Only loaded by listings: `Some(None)` when no maintenance window is in effect.
+    #[serde(skip)]
+    pub maintenance: Option<Option<Occurrence>>,
+    /// Only loaded by listings: whether the owner pinned the server.
+    #[serde(skip)]
+    pub pinned: Option<bool>,
+}
+
+/// Parses a server address: either a bare `IpAddr` (`10.0.0.1`, `fe80::1`) or a
+/// CIDR prefix (`10.0.0.0/24`).
+pub fn parse_server_address(value: &str) -> Result<IpNetwork, String> {
+    let value = value.trim();
+    match value.split_once('/') {
+        None => value
+            .parse::<IpAddr>()
+            .map(IpNetwork::from)
+            .map_err(|_| format!("`{}` is not a valid IP address", value)),
+        Some((addr, prefix)) => {
+            let addr = addr
+                .parse::<IpAddr>()
+                .map_err(|_| format!("`{}` is not a valid IP address", addr))?;
+            let prefix = prefix
+                .parse::<u8>()
+                .map_err(|_| format!("`{}` is not a valid prefix length", prefix))?;
+            IpNetwork::new(addr, prefix)
+                .map_err(|_| format!("`/{}` is out of range for {}", prefix, addr))
+        }
+    }
+}
+
+/// Renders single hosts without their `/32` or `/128` suffix.
+pub fn format_server_address(ip: &IpNetwork) -> String {
+    if ip.prefix() == IpNetwork::from(ip.ip()).prefix() {
+        ip.ip().to_string()
+    } else {
+        ip.to_string()
+    }
+}
+
+pub fn serialize_server_address<S>(ip: &IpNetwork, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&format_server_address(ip))
+}
+
+pub fn deserialize_server_address<'de, D>(deserializer: D) -> Result<IpNetwork, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse_server_address(&value).map_err(de::Error::custom)
+}
+
+pub fn deserialize_optional_server_address<'de, D>(
+    deserializer: D,
+) -> Result<Option<IpNetwork>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => parse_server_address(&value)
+            .map(Some)
+            .map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Among which live servers an address may only be used once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressUniqueness {
+    /// Servers may share addresses.
+    None,
+    /// An owner cannot have two servers with the same address.
+    Owner,
+    /// No two servers may have the same address, whoever owns them.
+    Global,
+}
+
+impl FromStr for AddressUniqueness {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "none" => Ok(AddressUniqueness::None),
+            "owner" => Ok(AddressUniqueness::Owner),
+            "global" => Ok(AddressUniqueness::Global),
+            other => Err(format!(
+                "`{}` is not an address uniqueness, use none, owner or global",
+                other
+            )),
+        }
+    }
+}
+
+/// Addresses used by more than one of `servers`, each with the servers using it
+/// in their original order.
+pub fn duplicate_addresses(servers: Vec<ServerModel>) -> Vec<(IpNetwork, Vec<ServerModel>)> {
+    let mut by_address: BTreeMap<IpNetwork, Vec<ServerModel>> = BTreeMap::new();
+    for server in servers {
+        by_address.entry(server.ip).or_default().push(server);
+    }
+    by_address
+        .into_iter()
+        .filter(|(_, servers)| servers.len() > 1)
+        .collect()
+}
+
+/// Ports to health check, `0` is not a port.
+pub fn deserialize_check_ports<'de, D>(deserializer: D) -> Result<Vec<u16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ports = Vec::<u16>::deserialize(deserializer)?;
+    if ports.contains(&0) {
+        return Err(de::Error::custom("`0` is not a valid port"));
+    }
+    Ok(ports)
+}
+
+pub fn deserialize_optional_check_ports<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<u16>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_check_ports(deserializer).map(Some)
 }
 
 pub enum ServerError {
     InfraError(InfraError),
     InternalServerError,
     NotFound,
+    Conflict,
+    /// Another server already has the address.
+    Duplicate(String),
+    /// Other servers still depend on the server.
+    InUse(String),
+    BadRequest(String),
+}
+
+impl From<InfraError> for ServerError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => ServerError::NotFound,
+            InfraError::Conflict => ServerError::Conflict,
+            InfraError::Invalid(message) => ServerError::BadRequest(message),
+            InfraError::Duplicate(message) => ServerError::Duplicate(message),
+            InfraError::UniqueViolation(_) => ServerError::Duplicate(error.to_string()),
+            InfraError::InUse(message) => ServerError::InUse(message),
+            _ => ServerError::InfraError(error),
+        }
+    }
 }
 
 impl IntoResponse for ServerError {
@@ -23,6 +202,14 @@ impl IntoResponse for ServerError {
                 axum::http::StatusCode::NOT_FOUND,
                 format!("ServerModel with id has not been found"),
             ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("ServerModel has been modified since it was read, reload and retry"),
+            ),
+            Self::Duplicate(message) | Self::InUse(message) => {
+                (axum::http::StatusCode::CONFLICT, message)
+            }
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
             Self::InfraError(err) => (
                 axum::http::StatusCode::INTERNAL_SERVER_ERROR,
                 format!("Internal server error: {}", err),
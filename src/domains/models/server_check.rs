@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerStatus {
+    /// Every checked port accepted a connection.
+    Up,
+    /// Some ports accepted a connection, others did not.
+    Degraded,
+    Down,
+}
+
+impl ServerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerStatus::Up => "up",
+            ServerStatus::Degraded => "degraded",
+            ServerStatus::Down => "down",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "up" => Some(ServerStatus::Up),
+            "degraded" => Some(ServerStatus::Degraded),
+            "down" => Some(ServerStatus::Down),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of one TCP connect attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortCheck {
+    pub port: u16,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Latest health check of a server.
+#[derive(Debug, Clone)]
+pub struct ServerCheckModel {
+    pub status: ServerStatus,
+    /// Slowest successful connect, `None` when no port answered.
+    pub latency_ms: Option<i32>,
+    pub ports: Vec<PortCheck>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ServerCheckModel {
+    pub fn from_ports(ports: Vec<PortCheck>, checked_at: DateTime<Utc>) -> Self {
+        let open = ports.iter().filter(|port| port.ok).count();
+        let status = if open == 0 {
+            ServerStatus::Down
+        } else if open == ports.len() {
+            ServerStatus::Up
+        } else {
+            ServerStatus::Degraded
+        };
+        let latency_ms = ports.iter().filter_map(|port| port.latency_ms).max();
+
+        ServerCheckModel {
+            status,
+            latency_ms,
+            ports,
+            checked_at,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use ipnetwork::IpNetwork;
+
+use super::labels::{check_key, check_value, format_labels, parse_labels, Labels};
+use super::server::{format_server_address, parse_server_address, ServerModel};
+
+pub const EXPORT_COLUMNS: [&str; 6] = ["id", "name", "ip", "labels", "version", "created_at"];
+
+/// Renders `servers` as CSV rows, preceded by the `EXPORT_COLUMNS` header when
+/// `with_header` is set. Exported files can be imported again unchanged.
+pub fn servers_to_csv(servers: &[ServerModel], with_header: bool) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    if with_header {
+        writer
+            .write_record(EXPORT_COLUMNS)
+            .map_err(|err| err.to_string())?;
+    }
+    for server in servers {
+        writer
+            .write_record([
+                server.id.clone(),
+                server.name.clone(),
+                format_server_address(&server.ip),
+                format_labels(&server.labels),
+                server.version.to_string(),
+                server.created_at.to_rfc3339(),
+            ])
+            .map_err(|err| err.to_string())?;
+    }
+    writer.into_inner().map_err(|err| err.to_string())
+}
+
+/// Server field a CSV column is imported into.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportField {
+    Name,
+    Ip,
+    /// `env=prod,role=db` shorthand.
+    Labels,
+    /// `labels.<key>`: the cell is the value of label `key`.
+    Label(String),
+}
+
+impl FromStr for ImportField {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "name" => Ok(ImportField::Name),
+            "ip" => Ok(ImportField::Ip),
+            "labels" => Ok(ImportField::Labels),
+            field => match field.strip_prefix("labels.") {
+                Some(key) => {
+                    check_key(key)?;
+                    Ok(ImportField::Label(key.to_string()))
+                }
+                None => Err(format!(
+                    "`{}` is not an importable field, use name, ip, labels or labels.<key>",
+                    field
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for ImportField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImportField::Name => write!(f, "name"),
+            ImportField::Ip => write!(f, "ip"),
+            ImportField::Labels => write!(f, "labels"),
+            ImportField::Label(key) => write!(f, "labels.{}", key),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerImportRow {
+    pub name: String,
+    pub ip: IpNetwork,
+    pub labels: Labels,
+}
+
+/// A parsed CSV file: one entry per data row with its line number, and the
+/// header columns that were not mapped to any field.
+#[derive(Debug)]
+pub struct ServerImport {
+    pub rows: Vec<(u64, Result<ServerImportRow, String>)>,
+    pub ignored_columns: Vec<String>,
+}
+
+/// Parses an import file. Columns are matched through `mapping` (CSV header to
+/// field name) first and by their own header otherwise; `name` and `ip` are
+/// required. Errors in individual rows are kept with the row, only problems
+/// with the header or the mapping fail the whole file.
+pub fn parse_server_csv(
+    data: &[u8],
+    mapping: &HashMap<String, String>,
+) -> Result<ServerImport, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+
+    let headers = reader
+        .headers()
+        .map_err(|err| format!("Unreadable CSV header: {}", err))?
+        .clone();
+
+    if let Some(unknown) = mapping
+        .keys()
+        .find(|column| !headers.iter().any(|header| header == column.as_str()))
+    {
+        return Err(format!(
+            "Mapped column `{}` is not in the CSV header",
+            unknown
+        ));
+    }
+
+    let mut columns = Vec::new();
+    let mut ignored_columns = Vec::new();
+    for (index, header) in headers.iter().enumerate() {
+        let field = match mapping.get(header) {
+            Some(field) => field
+                .parse::<ImportField>()
+                .map_err(|err| format!("Column `{}`: {}", header, err))?,
+            None => match header.to_lowercase().parse::<ImportField>() {
+                Ok(field) => field,
+                Err(_) => {
+                    ignored_columns.push(header.to_string());
+                    continue;
+                }
+            },
+        };
+        if columns.iter().any(|(_, mapped)| *mapped == field) {
+            return Err(format!("More than one column is mapped to `{}`", field));
+        }
+        columns.push((index, field));
+    }
+
+    for required in [ImportField::Name, ImportField::Ip] {
+        if !columns.iter().any(|(_, field)| *field == required) {
+            return Err(format!("No column is mapped to `{}`", required));
+        }
+    }
+
+    let mut seen_names: HashMap<String, u64> = HashMap::new();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let (line, row) = match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |position| position.line());
+                let row = parse_row(&record, &columns).and_then(|row| {
+                    match seen_names.insert(row.name.clone(), line) {
+                        Some(first) => Err(format!(
+                            "Name `{}` already appears on line {}",
+                            row.name, first
+                        )),
+                        None => Ok(row),
+                    }
+                });
+                (line, row)
+            }
+            Err(err) => (
+                err.position().map_or(0, |position| position.line()),
+                Err(err.to_string()),
+            ),
+        };
+        rows.push((line, row));
+    }
+
+    Ok(ServerImport {
+        rows,
+        ignored_columns,
+    })
+}
+
+fn parse_row(
+    record: &csv::StringRecord,
+    columns: &[(usize, ImportField)],
+) -> Result<ServerImportRow, String> {
+    let mut name = None;
+    let mut ip = None;
+    let mut labels = Labels::new();
+    let mut label_columns = Vec::new();
+
+    for (index, field) in columns {
+        let cell = record.get(*index).unwrap_or_default();
+        match field {
+            ImportField::Name if !cell.is_empty() => name = Some(cell.to_string()),
+            ImportField::Name => return Err(String::from("Name is empty")),
+            ImportField::Ip => ip = Some(parse_server_address(cell)?),
+            ImportField::Labels => labels.extend(parse_labels(cell)?),
+            ImportField::Label(key) if !cell.is_empty() => {
+                check_value(key, cell)?;
+                label_columns.push((key.clone(), cell.to_string()));
+            }
+            ImportField::Label(_) => {}
+        }
+    }
+    // Dedicated `labels.<key>` columns win over the shorthand column.
+    labels.extend(label_columns);
+
+    Ok(ServerImportRow {
+        name: name.unwrap_or_default(),
+        ip: ip.ok_or_else(|| String::from("IP is empty"))?,
+        labels,
+    })
+}
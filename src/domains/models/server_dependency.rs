@@ -0,0 +1,73 @@
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+/// A server another one depends on directly.
+#[derive(Debug, Clone)]
+pub struct DependencyModel {
+    pub depends_on: Uuid,
+    pub name: String,
+    pub ip: IpNetwork,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A server that stops working when `depends_on` goes down, `depth` edges away
+/// from the server the graph was walked from.
+#[derive(Debug, Clone)]
+pub struct DependentModel {
+    pub id: Uuid,
+    pub name: String,
+    pub ip: IpNetwork,
+    pub depends_on: Uuid,
+    pub depth: i32,
+}
+
+#[derive(Debug)]
+pub enum DependencyError {
+    InfraError(InfraError),
+    NotFound,
+    /// The server already depends on the other one.
+    Conflict,
+    BadRequest(String),
+}
+
+impl From<InfraError> for DependencyError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => DependencyError::NotFound,
+            InfraError::UniqueViolation(_) => DependencyError::Conflict,
+            InfraError::Invalid(message) => DependencyError::BadRequest(message),
+            _ => DependencyError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for DependencyError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("ServerModel with id has not been found"),
+            ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("The server already depends on this server"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"ServerDependencyModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,104 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use super::server::ServerModel;
+
+/// Lower-cased, without a trailing dot. Every label is 1 to 63 letters, digits
+/// or hyphens and does not start or end with a hyphen (RFC 1123).
+pub fn parse_hostname(value: &str) -> Result<String, String> {
+    let hostname = value.trim().trim_end_matches('.').to_lowercase();
+    let valid = !hostname.is_empty()
+        && hostname.len() <= 253
+        && hostname.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if valid {
+        Ok(hostname)
+    } else {
+        Err(format!("`{}` is not a valid hostname", value.trim()))
+    }
+}
+
+/// An empty string means no hostname.
+pub fn deserialize_optional_hostname<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) if value.trim().is_empty() => Ok(None),
+        Some(value) => parse_hostname(&value).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Like `deserialize_optional_hostname`, but tells a missing field (`None`)
+/// apart from clearing the hostname (`Some(None)`).
+pub fn deserialize_hostname_change<'de, D>(
+    deserializer: D,
+) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_optional_hostname(deserializer).map(Some)
+}
+
+/// Outcome of looking up a server's hostname and the PTR records of its address.
+#[derive(Debug, Clone)]
+pub struct DnsCheckModel {
+    /// The values that were looked up.
+    pub hostname: Option<String>,
+    pub ip: IpNetwork,
+    pub addresses: Vec<IpAddr>,
+    pub forward_error: Option<String>,
+    pub ptr_names: Vec<String>,
+    pub reverse_error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsIssue {
+    /// The hostname does not resolve.
+    Unresolvable,
+    /// The hostname resolves, but not to the recorded address.
+    AddressMismatch,
+    /// The address has PTR records, none of them is the hostname.
+    PtrMismatch,
+}
+
+impl DnsCheckModel {
+    /// Whether the check was made for the server as it is now.
+    pub fn is_current(&self, server: &ServerModel) -> bool {
+        self.hostname == server.hostname && self.ip == server.ip
+    }
+
+    /// Servers without a hostname have nothing to disagree with.
+    pub fn issues(&self) -> Vec<DnsIssue> {
+        let Some(hostname) = &self.hostname else {
+            return Vec::new();
+        };
+
+        let mut issues = Vec::new();
+        if self.forward_error.is_some() {
+            issues.push(DnsIssue::Unresolvable);
+        } else if !self.addresses.contains(&self.ip.ip()) {
+            issues.push(DnsIssue::AddressMismatch);
+        }
+        if !self.ptr_names.is_empty()
+            && !self
+                .ptr_names
+                .iter()
+                .any(|name| name.trim_end_matches('.').eq_ignore_ascii_case(hostname))
+        {
+            issues.push(DnsIssue::PtrMismatch);
+        }
+        issues
+    }
+}
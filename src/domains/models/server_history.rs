@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::server::ServerModel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerChangeAction {
+    Insert,
+    Update,
+    Delete,
+    Restore,
+}
+
+impl ServerChangeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerChangeAction::Insert => "insert",
+            ServerChangeAction::Update => "update",
+            ServerChangeAction::Delete => "delete",
+            ServerChangeAction::Restore => "restore",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "insert" => Some(ServerChangeAction::Insert),
+            "update" => Some(ServerChangeAction::Update),
+            "delete" => Some(ServerChangeAction::Delete),
+            "restore" => Some(ServerChangeAction::Restore),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded change of a server, with the state before and after it.
+#[derive(Debug, Clone)]
+pub struct ServerRevisionModel {
+    pub revision: i32,
+    pub action: ServerChangeAction,
+    pub actor_id: Option<Uuid>,
+    pub actor_username: Option<String>,
+    pub changed_at: DateTime<Utc>,
+    pub before: Option<ServerModel>,
+    pub after: Option<ServerModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: serde_json::Value,
+    pub to: serde_json::Value,
+}
+
+/// Lists every `ServerModel` field whose value differs between `from` and `to`.
+pub fn diff_servers(from: Option<&ServerModel>, to: Option<&ServerModel>) -> Vec<FieldChange> {
+    let from = snapshot_fields(from);
+    let to = snapshot_fields(to);
+
+    let mut fields: Vec<&String> = from.keys().chain(to.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let before = from.get(field).cloned().unwrap_or_default();
+            let after = to.get(field).cloned().unwrap_or_default();
+            (before != after).then(|| FieldChange {
+                field: field.clone(),
+                from: before,
+                to: after,
+            })
+        })
+        .collect()
+}
+
+fn snapshot_fields(server: Option<&ServerModel>) -> serde_json::Map<String, serde_json::Value> {
+    match server.map(serde_json::to_value) {
+        Some(Ok(serde_json::Value::Object(fields))) => fields,
+        _ => serde_json::Map::new(),
+    }
+}
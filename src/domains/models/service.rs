@@ -0,0 +1,130 @@
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use uuid::Uuid;
+
+use crate::infra::errors::InfraError;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceProtocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+impl ServiceProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceProtocol::Tcp => "tcp",
+            ServiceProtocol::Udp => "udp",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "tcp" => Some(ServiceProtocol::Tcp),
+            "udp" => Some(ServiceProtocol::Udp),
+            _ => None,
+        }
+    }
+}
+
+/// Something running on a server, e.g. `postgres` on 5432/tcp.
+#[derive(Debug, Clone)]
+pub struct ServiceModel {
+    pub id: Uuid,
+    pub name: String,
+    pub port: u16,
+    pub protocol: ServiceProtocol,
+    /// Team owning the service, which is not necessarily the server owner.
+    pub team: Option<String>,
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub fn check_service_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 255 {
+        Err(String::from(
+            "A service needs a name of 1 to 255 characters",
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Only absolute `http` and `https` links are accepted.
+pub fn check_service_url(url: &str) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"));
+    match rest {
+        Some(rest) if !rest.is_empty() && !url.contains(char::is_whitespace) => Ok(()),
+        _ => Err(format!("`{}` is not an http(s) URL", url)),
+    }
+}
+
+/// A service port, `0` is not a port.
+pub fn deserialize_service_port<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match u16::deserialize(deserializer)? {
+        0 => Err(de::Error::custom("`0` is not a valid port")),
+        port => Ok(port),
+    }
+}
+
+pub fn deserialize_optional_service_port<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_service_port(deserializer).map(Some)
+}
+
+#[derive(Debug)]
+pub enum ServiceError {
+    InfraError(InfraError),
+    NotFound,
+    /// Another service of the server already uses the port and protocol.
+    Conflict,
+    BadRequest(String),
+}
+
+impl From<InfraError> for ServiceError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => ServiceError::NotFound,
+            InfraError::UniqueViolation(_) => ServiceError::Conflict,
+            InfraError::Invalid(message) => ServiceError::BadRequest(message),
+            _ => ServiceError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("ServiceModel with id has not been found"),
+            ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("The server already has a service on this port and protocol"),
+            ),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"ServiceModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
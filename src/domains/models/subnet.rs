@@ -0,0 +1,318 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{de, Deserialize, Deserializer};
+use uuid::Uuid;
+
+use super::server::parse_server_address;
+use crate::infra::errors::InfraError;
+
+#[derive(Debug, Clone)]
+pub struct SubnetModel {
+    pub id: Uuid,
+    pub cidr: IpNetwork,
+    pub gateway: Option<IpAddr>,
+    pub vlan: Option<i32>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How much of a subnet is taken by live servers. `capacity` leaves out the
+/// addresses that are never handed out.
+#[derive(Debug, Clone)]
+pub struct SubnetUsage {
+    pub capacity: u128,
+    pub used: u128,
+    pub next_free: Option<IpAddr>,
+}
+
+impl SubnetModel {
+    /// Addresses that are never handed out, see `is_network_or_broadcast`, and the gateway.
+    pub fn is_reserved(&self, address: IpAddr) -> bool {
+        is_network_or_broadcast(&self.cidr, address) || self.gateway == Some(address)
+    }
+
+    pub fn capacity(&self) -> u128 {
+        let host_bits = u32::from(max_prefix(&self.cidr) - self.cidr.prefix());
+        let size = 1u128.checked_shl(host_bits).unwrap_or(u128::MAX);
+        let reserved = [
+            host_bits > 1,
+            host_bits > 1 && self.cidr.is_ipv4(),
+            self.gateway.is_some(),
+        ];
+        size - reserved.iter().filter(|reserved| **reserved).count() as u128
+    }
+
+    /// The lowest address that is neither reserved nor in `used`.
+    pub fn next_free(&self, used: &HashSet<IpAddr>) -> Option<IpAddr> {
+        self.cidr
+            .iter()
+            .find(|address| !self.is_reserved(*address) && !used.contains(address))
+    }
+
+    pub fn usage(&self, used: &HashSet<IpAddr>) -> SubnetUsage {
+        SubnetUsage {
+            capacity: self.capacity(),
+            used: used
+                .iter()
+                .filter(|address| self.cidr.contains(**address) && !self.is_reserved(**address))
+                .count() as u128,
+            next_free: self.next_free(used),
+        }
+    }
+}
+
+impl SubnetUsage {
+    /// Share of the capacity in use as a percentage with two decimals, a
+    /// subnet without any address to hand out counts as full.
+    pub fn utilisation(&self) -> f64 {
+        if self.capacity == 0 {
+            return 100.0;
+        }
+        (self.used as f64 / self.capacity as f64 * 10_000.0).round() / 100.0
+    }
+}
+
+/// The network address and the IPv4 broadcast address. Point to point `/31`
+/// and `/127` networks and single hosts use every address.
+fn is_network_or_broadcast(cidr: &IpNetwork, address: IpAddr) -> bool {
+    let host_bits = max_prefix(cidr) - cidr.prefix();
+    host_bits > 1 && (address == cidr.network() || (cidr.is_ipv4() && address == cidr.broadcast()))
+}
+
+fn max_prefix(cidr: &IpNetwork) -> u8 {
+    if cidr.is_ipv4() {
+        32
+    } else {
+        128
+    }
+}
+
+/// Reads a network such as `10.0.0.0/24`; host bits have to be zero.
+pub fn parse_cidr(value: &str) -> Result<IpNetwork, String> {
+    let cidr = parse_server_address(value)?;
+    if cidr.ip() != cidr.network() {
+        return Err(format!(
+            "`{}` has host bits set, did you mean `{}/{}`?",
+            value.trim(),
+            cidr.network(),
+            cidr.prefix()
+        ));
+    }
+    Ok(cidr)
+}
+
+pub fn deserialize_cidr<'de, D>(deserializer: D) -> Result<IpNetwork, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse_cidr(&value).map_err(de::Error::custom)
+}
+
+pub fn check_gateway(cidr: &IpNetwork, gateway: IpAddr) -> Result<(), String> {
+    if !cidr.contains(gateway) || is_network_or_broadcast(cidr, gateway) {
+        return Err(format!(
+            "`{}` is not a usable address of `{}`",
+            gateway, cidr
+        ));
+    }
+    Ok(())
+}
+
+pub fn check_vlan(vlan: i32) -> Result<(), String> {
+    if !(1..=4094).contains(&vlan) {
+        return Err(format!("VLAN `{}` is out of range 1-4094", vlan));
+    }
+    Ok(())
+}
+
+pub fn check_description(description: &str) -> Result<(), String> {
+    if description.len() > 1024 {
+        return Err(String::from("A description takes at most 1024 characters"));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SubnetError {
+    InfraError(InfraError),
+    NotFound,
+    /// The owner already has a subnet with the same range.
+    Conflict,
+    /// The subnet overlaps another one, or the address picked for a server is
+    /// already taken.
+    Duplicate(String),
+    BadRequest(String),
+}
+
+impl From<InfraError> for SubnetError {
+    fn from(error: InfraError) -> Self {
+        match error {
+            InfraError::NotFound => SubnetError::NotFound,
+            InfraError::UniqueViolation(_) => SubnetError::Conflict,
+            InfraError::Invalid(message) => SubnetError::BadRequest(message),
+            InfraError::Duplicate(message) => SubnetError::Duplicate(message),
+            _ => SubnetError::InfraError(error),
+        }
+    }
+}
+
+impl IntoResponse for SubnetError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, err_msg) = match self {
+            Self::NotFound => (
+                axum::http::StatusCode::NOT_FOUND,
+                String::from("SubnetModel with id has not been found"),
+            ),
+            Self::Conflict => (
+                axum::http::StatusCode::CONFLICT,
+                String::from("A subnet with this range already exists"),
+            ),
+            Self::Duplicate(message) => (axum::http::StatusCode::CONFLICT, message),
+            Self::BadRequest(message) => (axum::http::StatusCode::BAD_REQUEST, message),
+            Self::InfraError(err) => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Internal server error: {}", err),
+            ),
+        };
+        (
+            status,
+            axum::Json(
+                serde_json::json!({"resource":"SubnetModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
+            ),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subnet(cidr: &str, gateway: Option<&str>) -> SubnetModel {
+        SubnetModel {
+            id: Uuid::nil(),
+            cidr: cidr.parse().unwrap(),
+            gateway: gateway.map(|gateway| gateway.parse().unwrap()),
+            vlan: None,
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn used(addresses: &[&str]) -> HashSet<IpAddr> {
+        addresses.iter().map(|address| ip(address)).collect()
+    }
+
+    #[test]
+    fn capacity_leaves_out_reserved_addresses() {
+        assert_eq!(subnet("10.0.0.0/24", None).capacity(), 254);
+        assert_eq!(subnet("10.0.0.0/24", Some("10.0.0.1")).capacity(), 253);
+        assert_eq!(subnet("10.0.0.0/30", None).capacity(), 2);
+        assert_eq!(subnet("10.0.0.0/31", None).capacity(), 2);
+        assert_eq!(subnet("10.0.0.0/31", Some("10.0.0.0")).capacity(), 1);
+        assert_eq!(subnet("10.0.0.5/32", None).capacity(), 1);
+        assert_eq!(subnet("10.0.0.5/32", Some("10.0.0.5")).capacity(), 0);
+        assert_eq!(subnet("2001:db8::/64", None).capacity(), (1 << 64) - 1);
+        assert_eq!(subnet("2001:db8::/126", Some("2001:db8::1")).capacity(), 2);
+        assert_eq!(subnet("2001:db8::/127", None).capacity(), 2);
+        assert_eq!(subnet("2001:db8::1/128", None).capacity(), 1);
+        assert_eq!(subnet("::/0", None).capacity(), u128::MAX - 1);
+    }
+
+    #[test]
+    fn network_and_broadcast_only_for_larger_ranges() {
+        let cidr = |value: &str| value.parse::<IpNetwork>().unwrap();
+
+        assert!(is_network_or_broadcast(
+            &cidr("10.0.0.0/24"),
+            ip("10.0.0.0")
+        ));
+        assert!(is_network_or_broadcast(
+            &cidr("10.0.0.0/24"),
+            ip("10.0.0.255")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("10.0.0.0/24"),
+            ip("10.0.0.1")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("10.0.0.0/31"),
+            ip("10.0.0.0")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("10.0.0.0/31"),
+            ip("10.0.0.1")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("10.0.0.5/32"),
+            ip("10.0.0.5")
+        ));
+        // IPv6 has no broadcast address, only the subnet-router anycast one.
+        assert!(is_network_or_broadcast(
+            &cidr("2001:db8::/64"),
+            ip("2001:db8::")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("2001:db8::/64"),
+            ip("2001:db8::ffff:ffff:ffff:ffff")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("2001:db8::/127"),
+            ip("2001:db8::")
+        ));
+        assert!(!is_network_or_broadcast(
+            &cidr("2001:db8::1/128"),
+            ip("2001:db8::1")
+        ));
+    }
+
+    #[test]
+    fn next_free_skips_reserved_and_used_addresses() {
+        let lan = subnet("10.0.0.0/29", Some("10.0.0.1"));
+        assert_eq!(lan.next_free(&used(&[])), Some(ip("10.0.0.2")));
+        assert_eq!(
+            lan.next_free(&used(&["10.0.0.2", "10.0.0.4"])),
+            Some(ip("10.0.0.3"))
+        );
+        let taken = used(&["10.0.0.2", "10.0.0.3", "10.0.0.4", "10.0.0.5", "10.0.0.6"]);
+        assert_eq!(lan.next_free(&taken), None);
+
+        let link = subnet("10.0.0.0/31", None);
+        assert_eq!(link.next_free(&used(&[])), Some(ip("10.0.0.0")));
+        assert_eq!(link.next_free(&used(&["10.0.0.0"])), Some(ip("10.0.0.1")));
+
+        let host = subnet("10.0.0.5/32", None);
+        assert_eq!(host.next_free(&used(&[])), Some(ip("10.0.0.5")));
+        assert_eq!(host.next_free(&used(&["10.0.0.5"])), None);
+
+        let v6 = subnet("2001:db8::/64", Some("2001:db8::1"));
+        assert_eq!(v6.next_free(&used(&[])), Some(ip("2001:db8::2")));
+        let p2p = subnet("2001:db8::/127", None);
+        assert_eq!(p2p.next_free(&used(&[])), Some(ip("2001:db8::")));
+    }
+
+    #[test]
+    fn usage_counts_only_usable_addresses_inside_the_range() {
+        let lan = subnet("10.0.0.0/29", Some("10.0.0.1"));
+        let usage = lan.usage(&used(&[
+            "10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.7", "10.0.1.2",
+        ]));
+        assert_eq!(usage.capacity, 5);
+        assert_eq!(usage.used, 2);
+        assert_eq!(usage.next_free, Some(ip("10.0.0.4")));
+        assert_eq!(usage.utilisation(), 40.0);
+
+        let full = subnet("10.0.0.5/32", Some("10.0.0.5")).usage(&used(&[]));
+        assert_eq!(full.next_free, None);
+        assert_eq!(full.utilisation(), 100.0);
+    }
+}
@@ -0,0 +1,125 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{de, Deserialize, Deserializer};
+
+/// Length of an uptime report, written as `24h`, `7d` or `30d`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UptimeWindow {
+    amount: i64,
+    unit: char,
+}
+
+impl UptimeWindow {
+    const MAX_DAYS: i64 = 365;
+
+    pub fn duration(&self) -> Duration {
+        match self.unit {
+            'h' => Duration::hours(self.amount),
+            _ => Duration::days(self.amount),
+        }
+    }
+}
+
+impl Default for UptimeWindow {
+    fn default() -> Self {
+        UptimeWindow {
+            amount: 24,
+            unit: 'h',
+        }
+    }
+}
+
+impl FromStr for UptimeWindow {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{}` is not a window like `24h`, `7d` or `30d`", value);
+        let unit = value.chars().last().ok_or_else(invalid)?;
+        if !matches!(unit, 'h' | 'd') {
+            return Err(invalid());
+        }
+        let amount = value[..value.len() - 1]
+            .parse::<i64>()
+            .map_err(|_| invalid())?;
+
+        // Bounded before a `Duration` is built, which panics when out of range.
+        let max_amount = match unit {
+            'h' => Self::MAX_DAYS * 24,
+            _ => Self::MAX_DAYS,
+        };
+        if !(1..=max_amount).contains(&amount) {
+            return Err(format!(
+                "`{}` must be between 1h and {}d",
+                value,
+                Self::MAX_DAYS
+            ));
+        }
+        Ok(UptimeWindow { amount, unit })
+    }
+}
+
+impl fmt::Display for UptimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.unit)
+    }
+}
+
+pub fn deserialize_uptime_window<'de, D>(deserializer: D) -> Result<UptimeWindow, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => value.parse().map_err(de::Error::custom),
+        None => Ok(UptimeWindow::default()),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OutageModel {
+    pub started_at: DateTime<Utc>,
+    /// `None` while the server is still down.
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UptimeReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub samples: i64,
+    pub up_samples: i64,
+    pub latency_p50_ms: Option<i32>,
+    pub latency_p95_ms: Option<i32>,
+    /// Whether the numbers come from hourly rollups; latency percentiles are
+    /// then approximated from the per-hour percentiles.
+    pub downsampled: bool,
+    /// Outages overlapping the window, clipped to its start.
+    pub outages: Vec<OutageModel>,
+}
+
+impl UptimeReport {
+    pub fn uptime_percent(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.up_samples as f64 * 100.0 / self.samples as f64)
+    }
+}
+
+/// Nearest-rank percentile of `(value, weight)` pairs, `p` in `0.0..=1.0`.
+pub fn weighted_percentile(mut values: Vec<(i32, i64)>, p: f64) -> Option<i32> {
+    values.retain(|(_, weight)| *weight > 0);
+    values.sort_unstable();
+    let total: i64 = values.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let rank = ((p * total as f64).ceil() as i64).max(1);
+    let mut seen = 0;
+    for (value, weight) in values {
+        seen += weight;
+        if seen >= rank {
+            return Some(value);
+        }
+    }
+    None
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use uuid::Uuid;
+
+use super::{adapt_api_key_to_api_key_response, CreateApiKeyQuery, CreatedApiKeyResponse};
+use crate::domains::models::api_key::{generate_api_key, hash_api_key, ApiKeyError};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_api_key): JsonExtractor<CreateApiKeyQuery>,
+) -> Result<Json<CreatedApiKeyResponse>, ApiKeyError> {
+    let name = new_api_key.name.trim().to_string();
+    if name.is_empty() || name.len() > 255 {
+        return Err(ApiKeyError::BadRequest(String::from(
+            "An API key needs a name of 1 to 255 characters",
+        )));
+    }
+
+    let key = generate_api_key();
+    let new_api_key_db = respository::api_keys::NewApiKeyDB {
+        id: Uuid::new_v4(),
+        user_id: current_user.user_id,
+        token: hash_api_key(&key),
+        name,
+    };
+
+    let api_key = respository::api_keys::insert(&state.pool, new_api_key_db).await?;
+
+    Ok(Json(CreatedApiKeyResponse {
+        api_key: adapt_api_key_to_api_key_response(api_key),
+        key,
+    }))
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use super::{adapt_api_key_to_api_key_response, ListApiKeysResponse};
+use crate::domains::models::api_key::ApiKeyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListApiKeysResponse>, ApiKeyError> {
+    let api_keys = respository::api_keys::get_all(&state.pool, current_user.user_id).await?;
+
+    Ok(Json(ListApiKeysResponse {
+        api_keys: api_keys
+            .into_iter()
+            .map(adapt_api_key_to_api_key_response)
+            .collect(),
+    }))
+}
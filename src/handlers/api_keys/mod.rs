@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::api_key::ApiKeyModel;
+
+pub mod create_api_key;
+pub mod list_api_keys;
+pub mod revoke_api_key;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct CreateApiKeyQuery {
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    id: String,
+    name: String,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Only returned on creation, the key cannot be read back later.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    api_key: ApiKeyResponse,
+    key: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListApiKeysResponse {
+    api_keys: Vec<ApiKeyResponse>,
+}
+
+fn adapt_api_key_to_api_key_response(api_key: ApiKeyModel) -> ApiKeyResponse {
+    ApiKeyResponse {
+        id: api_key.id.to_string(),
+        name: api_key.name,
+        created_at: api_key.created_at,
+        last_used_at: api_key.last_used_at,
+    }
+}
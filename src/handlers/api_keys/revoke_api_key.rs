@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::api_key::ApiKeyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Path(api_key_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), ApiKeyError> {
+    respository::api_keys::delete(&state.pool, current_user.user_id, api_key_id).await?;
+
+    Ok(())
+}
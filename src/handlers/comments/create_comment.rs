@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{
+    adapt_comment_to_comment_response, thread_response, CommentBodyQuery, CommentsFormat,
+    CommentsFormatQuery,
+};
+use crate::domains::models::comment::{check_body, CommentError};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::utils::{JsonExtractor, QueryExtractor};
+use crate::AppState;
+
+/// Adds a comment by the signed in user. With `?format=html` the answer is
+/// the updated thread.
+pub async fn create_comment(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    QueryExtractor(query): QueryExtractor<CommentsFormatQuery>,
+    JsonExtractor(new_comment): JsonExtractor<CommentBodyQuery>,
+) -> Result<Response, CommentError> {
+    check_body(&new_comment.body).map_err(CommentError::BadRequest)?;
+    let new_comment_db = respository::server_comments::NewCommentDB {
+        id: Uuid::new_v4(),
+        server_id,
+        author_id: current_user.user_id,
+        body: new_comment.body,
+    };
+
+    let comment =
+        respository::server_comments::insert(&state.pool, current_user.user_id, new_comment_db)
+            .await?;
+
+    Ok(match query.format {
+        CommentsFormat::Json => Json(adapt_comment_to_comment_response(comment)).into_response(),
+        CommentsFormat::Html => {
+            let comments =
+                respository::server_comments::get_all(&state.pool, current_user.user_id, server_id)
+                    .await?;
+            thread_response(server_id, &comments, current_user.user_id)
+        }
+    })
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use uuid::Uuid;
+
+use super::{thread_response, CommentsFormat, CommentsFormatQuery};
+use crate::domains::models::comment::CommentError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::QueryExtractor, AppState};
+
+/// Removes a comment, only its author may.
+pub async fn delete_comment(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+    QueryExtractor(query): QueryExtractor<CommentsFormatQuery>,
+) -> Result<Response, CommentError> {
+    let comment =
+        respository::server_comments::get(&state.pool, current_user.user_id, server_id, comment_id)
+            .await?;
+    if comment.author_id != Some(current_user.user_id) {
+        return Err(CommentError::Forbidden);
+    }
+
+    respository::server_comments::delete(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        comment_id,
+        current_user.user_id,
+    )
+    .await?;
+
+    Ok(match query.format {
+        CommentsFormat::Json => ().into_response(),
+        CommentsFormat::Html => {
+            let comments =
+                respository::server_comments::get_all(&state.pool, current_user.user_id, server_id)
+                    .await?;
+            thread_response(server_id, &comments, current_user.user_id)
+        }
+    })
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{
+    adapt_comment_to_comment_response, thread_response, CommentsFormat, CommentsFormatQuery,
+    ListCommentsResponse,
+};
+use crate::domains::models::comment::CommentError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::QueryExtractor, AppState};
+
+/// The thread of a server, oldest first. `?format=html` renders it as a
+/// fragment for the detail view.
+pub async fn list_comments(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    QueryExtractor(query): QueryExtractor<CommentsFormatQuery>,
+) -> Result<Response, CommentError> {
+    let comments =
+        respository::server_comments::get_all(&state.pool, current_user.user_id, server_id).await?;
+
+    Ok(match query.format {
+        CommentsFormat::Json => Json(ListCommentsResponse {
+            server_id: server_id.to_string(),
+            comments: comments
+                .into_iter()
+                .map(adapt_comment_to_comment_response)
+                .collect(),
+        })
+        .into_response(),
+        CommentsFormat::Html => thread_response(server_id, &comments, current_user.user_id),
+    })
+}
@@ -0,0 +1,119 @@
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domains::models::comment::{escape_html, render_markdown, CommentModel};
+
+pub mod create_comment;
+pub mod delete_comment;
+pub mod list_comments;
+pub mod update_comment;
+
+// req & res
+
+/// `html` answers with the rendered thread, ready to be swapped in by htmx.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentsFormat {
+    #[default]
+    Json,
+    Html,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommentsFormatQuery {
+    #[serde(default)]
+    format: CommentsFormat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommentBodyQuery {
+    body: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentResponse {
+    id: String,
+    server_id: String,
+    author_id: Option<String>,
+    author: Option<String>,
+    body: String,
+    body_html: String,
+    created_at: DateTime<Utc>,
+    edited_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListCommentsResponse {
+    server_id: String,
+    comments: Vec<CommentResponse>,
+}
+
+fn adapt_comment_to_comment_response(comment: CommentModel) -> CommentResponse {
+    CommentResponse {
+        id: comment.id.to_string(),
+        server_id: comment.server_id.to_string(),
+        author_id: comment.author_id.map(|author_id| author_id.to_string()),
+        author: comment.author,
+        body_html: render_markdown(&comment.body),
+        body: comment.body,
+        created_at: comment.created_at,
+        edited_at: comment.edited_at,
+    }
+}
+
+/// The thread as an HTML fragment. The author of a comment gets controls to
+/// edit and delete it; every control swaps the whole thread with the answer.
+fn thread_response(server_id: Uuid, comments: &[CommentModel], viewer_id: Uuid) -> Response {
+    let base = format!("/v1/servers/{}/comments", server_id);
+    let mut html = format!("<div class=\"comments\" id=\"comments-{}\">", server_id);
+
+    if comments.is_empty() {
+        html.push_str("<p class=\"text-xs text-gray-500\">No comments yet</p>");
+    }
+    for comment in comments {
+        html.push_str(&format!(
+            "<article class=\"comment\" id=\"comment-{}\"><header class=\"text-xs text-gray-500\"><b>{}</b> <time datetime=\"{}\">{}</time>",
+            comment.id,
+            escape_html(comment.author.as_deref().unwrap_or("deleted user")),
+            comment.created_at.to_rfc3339(),
+            comment.created_at.format("%Y-%m-%d %H:%M UTC"),
+        ));
+        if let Some(edited_at) = comment.edited_at {
+            html.push_str(&format!(
+                " <span title=\"{}\">(edited)</span>",
+                edited_at.to_rfc3339()
+            ));
+        }
+        html.push_str("</header>");
+        html.push_str(&format!(
+            "<div class=\"comment-body\">{}</div>",
+            render_markdown(&comment.body)
+        ));
+
+        if comment.author_id == Some(viewer_id) {
+            html.push_str(&format!(
+                "<details><summary class=\"text-xs\">edit</summary>\
+                 <form hx-patch=\"{base}/{id}?format=html\" hx-ext=\"json-enc\" hx-target=\"closest .comments\" hx-swap=\"outerHTML\">\
+                 <textarea name=\"body\" required>{body}</textarea>\
+                 <button type=\"submit\">save</button></form></details>\
+                 <button class=\"text-xs\" hx-delete=\"{base}/{id}?format=html\" hx-target=\"closest .comments\" hx-swap=\"outerHTML\" hx-confirm=\"Delete this comment?\">delete</button>",
+                base = base,
+                id = comment.id,
+                body = escape_html(&comment.body),
+            ));
+        }
+        html.push_str("</article>");
+    }
+
+    html.push_str(&format!(
+        "<form hx-post=\"{}?format=html\" hx-ext=\"json-enc\" hx-target=\"closest .comments\" hx-swap=\"outerHTML\">\
+         <textarea name=\"body\" placeholder=\"Markdown\" required></textarea>\
+         <button type=\"submit\">comment</button></form></div>",
+        base
+    ));
+
+    ([(CONTENT_TYPE, "text/html; charset=utf-8")], html).into_response()
+}
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{
+    adapt_comment_to_comment_response, thread_response, CommentBodyQuery, CommentsFormat,
+    CommentsFormatQuery,
+};
+use crate::domains::models::comment::{check_body, CommentError};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::utils::{JsonExtractor, QueryExtractor};
+use crate::AppState;
+
+/// Replaces the body of a comment, only its author may.
+pub async fn update_comment(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, comment_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+    QueryExtractor(query): QueryExtractor<CommentsFormatQuery>,
+    JsonExtractor(changes): JsonExtractor<CommentBodyQuery>,
+) -> Result<Response, CommentError> {
+    check_body(&changes.body).map_err(CommentError::BadRequest)?;
+    let comment =
+        respository::server_comments::get(&state.pool, current_user.user_id, server_id, comment_id)
+            .await?;
+    if comment.author_id != Some(current_user.user_id) {
+        return Err(CommentError::Forbidden);
+    }
+
+    let comment = respository::server_comments::update(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        comment_id,
+        current_user.user_id,
+        changes.body,
+    )
+    .await?;
+
+    Ok(match query.format {
+        CommentsFormat::Json => Json(adapt_comment_to_comment_response(comment)).into_response(),
+        CommentsFormat::Html => {
+            let comments =
+                respository::server_comments::get_all(&state.pool, current_user.user_id, server_id)
+                    .await?;
+            thread_response(server_id, &comments, current_user.user_id)
+        }
+    })
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_dependency_to_dependency_response, CreateDependencyQuery, DependencyResponse};
+use crate::domains::models::server_dependency::DependencyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+/// Records that the server depends on `depends_on`; edges closing a cycle are rejected.
+pub async fn create_dependency(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_dependency): JsonExtractor<CreateDependencyQuery>,
+) -> Result<Json<DependencyResponse>, DependencyError> {
+    let new_dependency_db = respository::server_dependencies::NewServerDependencyDB {
+        server_id,
+        depends_on_id: new_dependency.depends_on,
+        created_by: current_user.user_id,
+    };
+
+    let dependency = respository::server_dependencies::insert(
+        &state.pool,
+        current_user.user_id,
+        new_dependency_db,
+    )
+    .await?;
+
+    Ok(Json(adapt_dependency_to_dependency_response(dependency)))
+}
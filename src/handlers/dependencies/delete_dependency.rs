@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::server_dependency::DependencyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_dependency(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, depends_on_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), DependencyError> {
+    respository::server_dependencies::delete(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        depends_on_id,
+    )
+    .await?;
+
+    Ok(())
+}
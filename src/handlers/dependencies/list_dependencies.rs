@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_dependency_to_dependency_response, ListDependenciesResponse};
+use crate::domains::models::server_dependency::DependencyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_dependencies(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListDependenciesResponse>, DependencyError> {
+    let dependencies =
+        respository::server_dependencies::get_all(&state.pool, current_user.user_id, server_id)
+            .await?;
+
+    Ok(Json(ListDependenciesResponse {
+        server_id: server_id.to_string(),
+        dependencies: dependencies
+            .into_iter()
+            .map(adapt_dependency_to_dependency_response)
+            .collect(),
+    }))
+}
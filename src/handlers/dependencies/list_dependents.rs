@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_dependent_to_dependent_response, DependentsQuery, ListDependentsResponse};
+use crate::domains::models::server_dependency::DependencyError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::QueryExtractor, AppState};
+
+/// The servers affected when this one goes down, with `transitive=true` also
+/// the ones depending on those.
+pub async fn list_dependents(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    QueryExtractor(query): QueryExtractor<DependentsQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListDependentsResponse>, DependencyError> {
+    let dependents = respository::server_dependencies::get_dependents(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        query.transitive,
+    )
+    .await?;
+
+    Ok(Json(ListDependentsResponse {
+        server_id: server_id.to_string(),
+        transitive: query.transitive,
+        dependents: dependents
+            .into_iter()
+            .map(adapt_dependent_to_dependent_response)
+            .collect(),
+    }))
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domains::models::server_dependency::{DependencyModel, DependentModel};
+
+pub mod create_dependency;
+pub mod delete_dependency;
+pub mod list_dependencies;
+pub mod list_dependents;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct CreateDependencyQuery {
+    depends_on: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DependentsQuery {
+    #[serde(default)]
+    transitive: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyResponse {
+    depends_on: String,
+    name: String,
+    ip: String,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDependenciesResponse {
+    server_id: String,
+    dependencies: Vec<DependencyResponse>,
+}
+
+/// `depends_on` is the server the dependent was reached through, `depth` 1
+/// for direct dependents.
+#[derive(Debug, Serialize)]
+pub struct DependentResponse {
+    id: String,
+    name: String,
+    ip: String,
+    depends_on: String,
+    depth: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDependentsResponse {
+    server_id: String,
+    transitive: bool,
+    dependents: Vec<DependentResponse>,
+}
+
+fn adapt_dependency_to_dependency_response(dependency: DependencyModel) -> DependencyResponse {
+    DependencyResponse {
+        depends_on: dependency.depends_on.to_string(),
+        name: dependency.name,
+        ip: dependency.ip.to_string(),
+        created_at: dependency.created_at,
+    }
+}
+
+fn adapt_dependent_to_dependent_response(dependent: DependentModel) -> DependentResponse {
+    DependentResponse {
+        id: dependent.id.to_string(),
+        name: dependent.name,
+        ip: dependent.ip.to_string(),
+        depends_on: dependent.depends_on.to_string(),
+        depth: dependent.depth,
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::header::CONTENT_TYPE,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+
+use crate::{
+    domains::models::{
+        inventory::{ansible_inventory as build_inventory, ansible_inventory_ini, inventory_hosts},
+        server::ServerError,
+    },
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{self, servers::ServersFilter},
+    },
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{AnsibleInventoryQuery, InventoryFormat};
+
+/// Ansible dynamic inventory of the caller's servers. Accepts the
+/// `list_servers` filters, e.g. `?label_selector=env=prod`, to scope it.
+pub async fn ansible_inventory(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    QueryExtractor(query): QueryExtractor<AnsibleInventoryQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Response, ServerError> {
+    let servers =
+        respository::servers::get_all_matching(&state.pool, current_user.user_id, filter).await?;
+    let hosts = inventory_hosts(servers);
+
+    Ok(match query.format {
+        InventoryFormat::Json => Json(build_inventory(&hosts)).into_response(),
+        InventoryFormat::Ini => (
+            [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+            ansible_inventory_ini(&hosts),
+        )
+            .into_response(),
+    })
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+pub mod ansible_inventory;
+
+// req & res
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InventoryFormat {
+    #[default]
+    Json,
+    Ini,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnsibleInventoryQuery {
+    #[serde(default)]
+    format: InventoryFormat,
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use chrono::Utc;
+
+use super::{ActiveMaintenanceFeedResponse, ActiveMaintenanceResponse};
+use crate::domains::models::{maintenance::MaintenanceError, server::format_server_address};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+/// The maintenance windows in effect right now on the key owner's servers, for
+/// monitors to silence alerts about planned downtime.
+pub async fn active_maintenance(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ActiveMaintenanceFeedResponse>, MaintenanceError> {
+    let now = Utc::now();
+    let candidates =
+        respository::maintenance::get_candidates(&state.pool, current_user.user_id, now).await?;
+
+    let active = candidates
+        .into_iter()
+        .filter_map(|(name, ip, window)| {
+            let occurrence = window.active_at(now)?;
+            Some(ActiveMaintenanceResponse {
+                server_id: window.server_id.to_string(),
+                name,
+                ip: format_server_address(&ip),
+                window_id: window.id.to_string(),
+                starts_at: occurrence.starts_at,
+                ends_at: occurrence.ends_at,
+                reason: window.reason,
+            })
+        })
+        .collect();
+
+    Ok(Json(ActiveMaintenanceFeedResponse { at: now, active }))
+}
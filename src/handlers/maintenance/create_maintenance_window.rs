@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    adapt_window_to_window_response, CreateMaintenanceWindowQuery, MaintenanceWindowResponse,
+};
+use crate::domains::models::maintenance::{check_reason, check_window, MaintenanceError};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn create_maintenance_window(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_window): JsonExtractor<CreateMaintenanceWindowQuery>,
+) -> Result<Json<MaintenanceWindowResponse>, MaintenanceError> {
+    check_window(new_window.starts_at, new_window.ends_at).map_err(MaintenanceError::BadRequest)?;
+    let reason = new_window
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty());
+    if let Some(reason) = &reason {
+        check_reason(reason).map_err(MaintenanceError::BadRequest)?;
+    }
+
+    let new_window_db = respository::maintenance::NewMaintenanceWindowDB {
+        id: Uuid::new_v4(),
+        server_id,
+        starts_at: new_window.starts_at,
+        ends_at: new_window.ends_at,
+        rrule: new_window.rrule.map(|recurrence| recurrence.to_string()),
+        reason,
+        created_by: current_user.user_id,
+    };
+
+    let window =
+        respository::maintenance::insert(&state.pool, current_user.user_id, new_window_db).await?;
+
+    Ok(Json(adapt_window_to_window_response(window, Utc::now())))
+}
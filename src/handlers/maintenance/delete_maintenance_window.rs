@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::maintenance::MaintenanceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_maintenance_window(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, window_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), MaintenanceError> {
+    respository::maintenance::delete(&state.pool, current_user.user_id, server_id, window_id)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{adapt_window_to_window_response, ListMaintenanceWindowsResponse};
+use crate::domains::models::maintenance::MaintenanceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_maintenance_windows(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListMaintenanceWindowsResponse>, MaintenanceError> {
+    let windows =
+        respository::maintenance::get_all(&state.pool, current_user.user_id, server_id).await?;
+
+    let now = Utc::now();
+    Ok(Json(ListMaintenanceWindowsResponse {
+        server_id: server_id.to_string(),
+        windows: windows
+            .into_iter()
+            .map(|window| adapt_window_to_window_response(window, now))
+            .collect(),
+    }))
+}
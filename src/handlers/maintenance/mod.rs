@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::maintenance::{
+    deserialize_optional_recurrence, MaintenanceWindowModel, Occurrence, Recurrence,
+};
+
+pub mod active_maintenance;
+pub mod create_maintenance_window;
+pub mod delete_maintenance_window;
+pub mod list_maintenance_windows;
+
+// req & res
+
+/// `starts_at` and `ends_at` bound the first occurrence; `rrule` repeats it,
+/// e.g. `FREQ=WEEKLY;BYDAY=SU;UNTIL=20241231T000000Z`.
+#[derive(Deserialize, Debug)]
+pub struct CreateMaintenanceWindowQuery {
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "deserialize_optional_recurrence")]
+    rrule: Option<Recurrence>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MaintenanceWindowResponse {
+    id: String,
+    server_id: String,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    rrule: Option<String>,
+    reason: Option<String>,
+    created_at: DateTime<Utc>,
+    active: bool,
+    /// The occurrence in effect or the next one, `null` once the window is over.
+    next: Option<Occurrence>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListMaintenanceWindowsResponse {
+    server_id: String,
+    windows: Vec<MaintenanceWindowResponse>,
+}
+
+/// A window in effect, with enough of its server for a monitor to match it.
+#[derive(Debug, Serialize)]
+pub struct ActiveMaintenanceResponse {
+    server_id: String,
+    name: String,
+    ip: String,
+    window_id: String,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveMaintenanceFeedResponse {
+    at: DateTime<Utc>,
+    active: Vec<ActiveMaintenanceResponse>,
+}
+
+fn adapt_window_to_window_response(
+    window: MaintenanceWindowModel,
+    now: DateTime<Utc>,
+) -> MaintenanceWindowResponse {
+    let next = window.next_at(now);
+    MaintenanceWindowResponse {
+        id: window.id.to_string(),
+        server_id: window.server_id.to_string(),
+        starts_at: window.starts_at,
+        ends_at: window.ends_at,
+        rrule: window.recurrence.map(|recurrence| recurrence.to_string()),
+        reason: window.reason,
+        created_at: window.created_at,
+        active: next.is_some_and(|occurrence| occurrence.starts_at <= now),
+        next,
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use uuid::Uuid;
+
+use super::{
+    adapt_metadata_schema_to_metadata_schema_response, parse_label_selector, parse_name,
+    parse_schema, CreateMetadataSchemaQuery, MetadataSchemaResponse,
+};
+use crate::domains::models::metadata::MetadataSchemaError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+/// Existing servers are not re-validated, they have to satisfy the schema on
+/// their next change.
+pub async fn create_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_schema): JsonExtractor<CreateMetadataSchemaQuery>,
+) -> Result<Json<MetadataSchemaResponse>, MetadataSchemaError> {
+    let new_schema_db = respository::metadata_schemas::NewMetadataSchemaDB {
+        id: Uuid::new_v4(),
+        name: parse_name(&new_schema.name)?,
+        label_selector: parse_label_selector(&new_schema.label_selector)?,
+        schema: parse_schema(new_schema.schema)?,
+        created_by: current_user.user_id,
+    };
+
+    let schema = respository::metadata_schemas::insert(&state.pool, new_schema_db).await?;
+
+    Ok(Json(adapt_metadata_schema_to_metadata_schema_response(
+        schema,
+    )))
+}
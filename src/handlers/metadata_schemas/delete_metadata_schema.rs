@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use uuid::Uuid;
+
+use crate::domains::models::metadata::MetadataSchemaError;
+use crate::infra::respository;
+use crate::AppState;
+
+pub async fn delete_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Path(schema_id): Path<Uuid>,
+) -> Result<(), MetadataSchemaError> {
+    respository::metadata_schemas::delete(&state.pool, schema_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Json};
+
+use super::{adapt_metadata_schema_to_metadata_schema_response, ListMetadataSchemasResponse};
+use crate::domains::models::metadata::MetadataSchemaError;
+use crate::infra::respository;
+use crate::AppState;
+
+pub async fn list_metadata_schemas(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<ListMetadataSchemasResponse>, MetadataSchemaError> {
+    let schemas = respository::metadata_schemas::get_all(&state.pool).await?;
+
+    Ok(Json(ListMetadataSchemasResponse {
+        metadata_schemas: schemas
+            .into_iter()
+            .map(adapt_metadata_schema_to_metadata_schema_response)
+            .collect(),
+    }))
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::labels::LabelSelector;
+use crate::domains::models::metadata::{check_schema, MetadataSchemaError, MetadataSchemaModel};
+
+pub mod create_metadata_schema;
+pub mod delete_metadata_schema;
+pub mod list_metadata_schemas;
+pub mod update_metadata_schema;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct CreateMetadataSchemaQuery {
+    name: String,
+    /// Servers the schema applies to, e.g. `env=prod`; empty for all servers.
+    #[serde(default)]
+    label_selector: String,
+    schema: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateMetadataSchemaQuery {
+    name: Option<String>,
+    label_selector: Option<String>,
+    schema: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetadataSchemaResponse {
+    id: String,
+    name: String,
+    label_selector: String,
+    schema: serde_json::Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListMetadataSchemasResponse {
+    metadata_schemas: Vec<MetadataSchemaResponse>,
+}
+
+fn adapt_metadata_schema_to_metadata_schema_response(
+    schema: MetadataSchemaModel,
+) -> MetadataSchemaResponse {
+    MetadataSchemaResponse {
+        id: schema.id.to_string(),
+        name: schema.name,
+        label_selector: schema.label_selector_text,
+        schema: schema.schema,
+        created_at: schema.created_at,
+        updated_at: schema.updated_at,
+    }
+}
+
+fn parse_name(name: &str) -> Result<String, MetadataSchemaError> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > 255 {
+        return Err(MetadataSchemaError::BadRequest(String::from(
+            "A metadata schema needs a name of 1 to 255 characters",
+        )));
+    }
+    Ok(name.to_string())
+}
+
+fn parse_label_selector(label_selector: &str) -> Result<String, MetadataSchemaError> {
+    let label_selector = label_selector.trim();
+    label_selector
+        .parse::<LabelSelector>()
+        .map_err(MetadataSchemaError::BadRequest)?;
+    Ok(label_selector.to_string())
+}
+
+fn parse_schema(schema: serde_json::Value) -> Result<serde_json::Value, MetadataSchemaError> {
+    check_schema(&schema).map_err(MetadataSchemaError::BadRequest)?;
+    Ok(schema)
+}
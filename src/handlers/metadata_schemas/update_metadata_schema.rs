@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    adapt_metadata_schema_to_metadata_schema_response, parse_label_selector, parse_name,
+    parse_schema, MetadataSchemaResponse, UpdateMetadataSchemaQuery,
+};
+use crate::domains::models::metadata::MetadataSchemaError;
+use crate::infra::respository;
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn update_metadata_schema(
+    State(state): State<Arc<AppState>>,
+    Path(schema_id): Path<Uuid>,
+    JsonExtractor(changes): JsonExtractor<UpdateMetadataSchemaQuery>,
+) -> Result<Json<MetadataSchemaResponse>, MetadataSchemaError> {
+    let update_schema_db = respository::metadata_schemas::UpdateMetadataSchemaDB {
+        name: changes.name.as_deref().map(parse_name).transpose()?,
+        label_selector: changes
+            .label_selector
+            .as_deref()
+            .map(parse_label_selector)
+            .transpose()?,
+        schema: changes.schema.map(parse_schema).transpose()?,
+        updated_at: Utc::now(),
+    };
+
+    let schema =
+        respository::metadata_schemas::update(&state.pool, schema_id, update_schema_db).await?;
+
+    Ok(Json(adapt_metadata_schema_to_metadata_schema_response(
+        schema,
+    )))
+}
@@ -1,2 +1,14 @@
+pub mod api_keys;
+pub mod comments;
+pub mod dependencies;
+pub mod inventory;
+pub mod maintenance;
+pub mod metadata_schemas;
+pub mod pins;
+pub mod sd;
+pub mod secrets;
 pub mod servers;
-pub mod user;
\ No newline at end of file
+pub mod services;
+pub mod subnets;
+pub mod user;
+pub mod views;
\ No newline at end of file
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use super::{adapt_pin_to_pin_response, ListPinsResponse};
+use crate::domains::models::server::ServerError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_pins(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListPinsResponse>, ServerError> {
+    let pins = respository::server_pins::get_all(&state.pool, current_user.user_id).await?;
+
+    Ok(Json(ListPinsResponse {
+        pins: pins.into_iter().map(adapt_pin_to_pin_response).collect(),
+    }))
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+pub mod list_pins;
+pub mod pin_server;
+pub mod unpin_server;
+
+// req & res
+
+#[derive(Debug, Serialize)]
+pub struct PinResponse {
+    server_id: String,
+    pinned_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListPinsResponse {
+    pins: Vec<PinResponse>,
+}
+
+fn adapt_pin_to_pin_response((server_id, pinned_at): (Uuid, DateTime<Utc>)) -> PinResponse {
+    PinResponse {
+        server_id: server_id.to_string(),
+        pinned_at,
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_pin_to_pin_response, PinResponse};
+use crate::domains::models::server::ServerError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+/// Keeps the server at the top of the caller's listings.
+pub async fn pin_server(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<PinResponse>, ServerError> {
+    let pinned_at =
+        respository::server_pins::pin(&state.pool, current_user.user_id, server_id).await?;
+
+    Ok(Json(adapt_pin_to_pin_response((server_id, pinned_at))))
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::server::ServerError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn unpin_server(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), ServerError> {
+    respository::server_pins::unpin(&state.pool, current_user.user_id, server_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+
+pub mod prometheus_sd;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct PrometheusSdQuery {
+    /// Comma separated, e.g. `9100,9256`. Defaults to `PROMETHEUS_SD_PORTS`.
+    ports: Option<String>,
+}
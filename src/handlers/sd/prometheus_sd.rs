@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{
+        header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use crate::{
+    config::config,
+    domains::models::{
+        inventory::{inventory_hosts, prometheus_targets},
+        server::ServerError,
+    },
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{self, servers::ServersFilter},
+    },
+    utils::{sha256_hex, QueryExtractor},
+    AppState,
+};
+
+use super::PrometheusSdQuery;
+
+/// Prometheus `http_sd_config` targets for the key owner's servers. The
+/// response carries an `ETag`; a refresh sending it back in `If-None-Match`
+/// gets an empty `304` while the targets are unchanged.
+pub async fn prometheus_sd(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    QueryExtractor(query): QueryExtractor<PrometheusSdQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+    headers: HeaderMap,
+) -> Result<Response, ServerError> {
+    let ports = match query.ports {
+        Some(ports) => ports
+            .split(',')
+            .map(|port| port.trim().parse::<u16>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ServerError::BadRequest(format!("`{}` is not a list of ports", ports)))?,
+        None => config().await.prometheus_sd_ports().to_vec(),
+    };
+
+    // Answers a refresh from a digest of the matching rows before loading them.
+    let fingerprint =
+        respository::servers::fingerprint(&state.pool, current_user.user_id, filter.clone())
+            .await?;
+    let etag = format!(
+        "\"{}\"",
+        sha256_hex(format!("{}:{:?}", fingerprint, ports).as_bytes())
+    );
+
+    let not_modified = headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|candidate| candidate.trim() == etag || candidate.trim() == "*");
+
+    if not_modified {
+        return Ok((StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response());
+    }
+
+    let servers =
+        respository::servers::get_all_matching(&state.pool, current_user.user_id, filter).await?;
+    let body = serde_json::to_string(&prometheus_targets(&inventory_hosts(servers), &ports))
+        .map_err(|_| ServerError::InternalServerError)?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, String::from("application/json")),
+            (ETAG, etag),
+        ],
+        body,
+    )
+        .into_response())
+}
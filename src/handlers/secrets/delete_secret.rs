@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::secret::SecretError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_secret(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, name)): Path<(Uuid, String)>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), SecretError> {
+    respository::server_secrets::delete(&state.pool, current_user.user_id, server_id, name).await?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_secret_to_secret_response, ListSecretsResponse};
+use crate::domains::models::secret::SecretError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_secrets(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListSecretsResponse>, SecretError> {
+    let secrets =
+        respository::server_secrets::get_all(&state.pool, current_user.user_id, server_id).await?;
+
+    Ok(Json(ListSecretsResponse {
+        server_id: server_id.to_string(),
+        secrets: secrets
+            .into_iter()
+            .map(adapt_secret_to_secret_response)
+            .collect(),
+    }))
+}
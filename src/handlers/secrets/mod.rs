@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::secret::SecretModel;
+
+pub mod delete_secret;
+pub mod list_secrets;
+pub mod put_secret;
+pub mod reveal_secret;
+
+// req & res
+// The types holding a value or a password do not implement `Debug`, so they
+// cannot end up in logs.
+
+#[derive(Deserialize)]
+pub struct PutSecretQuery {
+    value: String,
+}
+
+/// The password of the signed in user, asked again for every reveal.
+#[derive(Deserialize)]
+pub struct RevealSecretQuery {
+    pw: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SecretResponse {
+    server_id: String,
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListSecretsResponse {
+    server_id: String,
+    secrets: Vec<SecretResponse>,
+}
+
+#[derive(Serialize)]
+pub struct RevealSecretResponse {
+    server_id: String,
+    name: String,
+    value: String,
+    revealed_at: DateTime<Utc>,
+}
+
+fn adapt_secret_to_secret_response(secret: SecretModel) -> SecretResponse {
+    SecretResponse {
+        server_id: secret.server_id.to_string(),
+        name: secret.name,
+        created_at: secret.created_at,
+        updated_at: secret.updated_at,
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_secret_to_secret_response, PutSecretQuery, SecretResponse};
+use crate::domains::models::secret::{
+    check_secret_name, check_secret_value, secret_entry, SecretError,
+};
+use crate::infra::errors::InfraError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+/// Creates the secret or replaces its value.
+pub async fn put_secret(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, name)): Path<(Uuid, String)>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(secret): JsonExtractor<PutSecretQuery>,
+) -> Result<Json<SecretResponse>, SecretError> {
+    let vault = state.vault.as_ref().ok_or(SecretError::VaultDisabled)?;
+    check_secret_name(&name).map_err(SecretError::BadRequest)?;
+    check_secret_value(&secret.value).map_err(SecretError::BadRequest)?;
+
+    let sealed = vault
+        .seal(&secret_entry(server_id, &name), &secret.value)
+        .map_err(|error| {
+            tracing::error!(
+                "Could not seal secret {} of server {}: {}",
+                name,
+                server_id,
+                error
+            );
+            SecretError::InfraError(InfraError::InternalServerError)
+        })?;
+    let new_secret_db = respository::server_secrets::NewSecretDB {
+        server_id,
+        name,
+        nonce: sealed.nonce,
+        ciphertext: sealed.ciphertext,
+        created_by: current_user.user_id,
+    };
+
+    let secret =
+        respository::server_secrets::upsert(&state.pool, current_user.user_id, new_secret_db)
+            .await?;
+
+    Ok(Json(adapt_secret_to_secret_response(secret)))
+}
@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{RevealSecretQuery, RevealSecretResponse};
+use crate::domains::models::secret::{
+    secret_entry, SecretError, MAX_FAILED_REVEALS, REVEAL_LOCKOUT,
+};
+use crate::infra::errors::InfraError;
+use crate::infra::respository::server_secrets::RevealCheck;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+/// Returns the value in clear text once the user has entered their password
+/// again. Every attempt past the lookup is written to the audit table, and a
+/// reveal that cannot be recorded returns nothing. Once the user gave
+/// `MAX_FAILED_REVEALS` wrong passwords within `REVEAL_LOCKOUT` the password
+/// is not checked at all until older failures age out.
+pub async fn reveal_secret(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, name)): Path<(Uuid, String)>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(reveal): JsonExtractor<RevealSecretQuery>,
+) -> Result<impl IntoResponse, SecretError> {
+    let vault = state.vault.as_ref().ok_or(SecretError::VaultDisabled)?;
+    let user_id = current_user.user_id;
+
+    let sealed =
+        respository::server_secrets::get_sealed(&state.pool, user_id, server_id, name.clone())
+            .await?;
+
+    let check = respository::server_secrets::check_reveal(
+        &state.pool,
+        user_id,
+        server_id,
+        name.clone(),
+        reveal.pw,
+        Utc::now() - REVEAL_LOCKOUT,
+        MAX_FAILED_REVEALS,
+    )
+    .await?;
+    match check {
+        RevealCheck::Verified => {}
+        RevealCheck::LockedOut => {
+            tracing::warn!(
+                "User {} is locked out of revealing secret {} of server {}",
+                user_id,
+                name,
+                server_id
+            );
+            return Err(SecretError::TooManyAttempts);
+        }
+        RevealCheck::WrongPassword => {
+            tracing::warn!(
+                "User {} gave a wrong password revealing secret {} of server {}",
+                user_id,
+                name,
+                server_id
+            );
+            return Err(SecretError::Unauthorized);
+        }
+    }
+
+    let value = vault
+        .open(&secret_entry(server_id, &name), sealed)
+        .map_err(|error| {
+            tracing::error!(
+                "Could not open secret {} of server {}: {}",
+                name,
+                server_id,
+                error
+            );
+            SecretError::InfraError(InfraError::InternalServerError)
+        })?;
+    respository::server_secrets::record_reveal(&state.pool, user_id, server_id, name.clone(), true)
+        .await?;
+    tracing::info!(
+        "User {} revealed secret {} of server {}",
+        user_id,
+        name,
+        server_id
+    );
+
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(RevealSecretResponse {
+            server_id: server_id.to_string(),
+            name,
+            value,
+            revealed_at: Utc::now(),
+        }),
+    ))
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use uuid::Uuid;
+
+use super::{
+    adapt_bulk_outcome, check_bulk_size, pick_address, BulkCreateServersQuery, BulkItemStatus,
+    CreateServerQuery, NewServerAddress,
+};
+use crate::infra::respository::servers::{BulkMode, BulkOutcome, NewServerDB};
+use crate::infra::{jobs::dns_check, middleware::auth_middleware::CurrentUser, respository};
+use crate::{config::config, domains::models::server::ServerError, utils::JsonExtractor, AppState};
+
+/// Creates many servers in one transaction. Every item is validated before
+/// anything is written; in `all_or_nothing` mode one invalid item aborts the batch.
+pub async fn bulk_create_servers(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(bulk): JsonExtractor<BulkCreateServersQuery>,
+) -> Result<impl IntoResponse, ServerError> {
+    check_bulk_size(bulk.servers.len())?;
+    tracing::info!("Bulk creating {} servers", bulk.servers.len());
+
+    let mut invalid = Vec::new();
+    let mut valid = Vec::new();
+    let mut new_servers = Vec::new();
+    for (index, item) in bulk.servers.into_iter().enumerate() {
+        match serde_json::from_value::<CreateServerQuery>(item) {
+            Ok(new_server) => match pick_address(new_server.ip, new_server.subnet_id) {
+                Ok(NewServerAddress::Literal(ip)) => {
+                    valid.push(index);
+                    new_servers.push(NewServerDB {
+                        ip,
+                        name: new_server.name,
+                        owner_id: current_user.user_id,
+                        labels: respository::servers::labels_to_json(new_server.labels),
+                        check_ports: respository::servers::ports_to_db(new_server.check_ports),
+                        metadata: respository::servers::metadata_to_json(new_server.metadata),
+                        hostname: new_server.hostname,
+                    });
+                }
+                Ok(NewServerAddress::Subnet(_)) => invalid.push((
+                    index,
+                    String::from("Bulk requests take literal addresses, not `subnet_id`"),
+                )),
+                Err(message) => invalid.push((index, message)),
+            },
+            Err(error) => invalid.push((index, error.to_string())),
+        }
+    }
+
+    let outcome = if invalid.is_empty() || bulk.mode == BulkMode::BestEffort {
+        Some(
+            respository::servers::insert_many(
+                &state.pool,
+                new_servers,
+                bulk.mode,
+                config().await.address_uniqueness(),
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    if let Some(BulkOutcome::Committed(items)) = &outcome {
+        let created = items
+            .iter()
+            .filter_map(|item| item.as_ref().ok())
+            .filter_map(|server| Uuid::parse_str(&server.id).ok())
+            .collect();
+        dns_check::spawn_check(state.pool.clone(), state.resolver.clone(), created);
+    }
+
+    let report = adapt_bulk_outcome(bulk.mode, BulkItemStatus::Created, invalid, valid, outcome);
+    let status = if report.committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, [("HX-Trigger", "AddServerDone")], Json(report)))
+}
@@ -0,0 +1,60 @@
+use std::{collections::HashSet, sync::Arc};
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use uuid::Uuid;
+
+use super::{adapt_bulk_outcome, check_bulk_size, BulkDeleteServersQuery, BulkItemStatus};
+use crate::infra::respository::servers::BulkMode;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{domains::models::server::ServerError, utils::JsonExtractor, AppState};
+
+/// Moves many servers to the trash in one transaction, see `bulk_create_servers`.
+pub async fn bulk_delete_servers(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(bulk): JsonExtractor<BulkDeleteServersQuery>,
+) -> Result<impl IntoResponse, ServerError> {
+    check_bulk_size(bulk.ids.len())?;
+    tracing::info!("Bulk deleting {} servers", bulk.ids.len());
+
+    let mut invalid = Vec::new();
+    let mut valid = Vec::new();
+    let mut ids = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, item) in bulk.ids.into_iter().enumerate() {
+        match serde_json::from_value::<Uuid>(item) {
+            Ok(id) if !seen.insert(id) => {
+                invalid.push((index, format!("Server {} is listed more than once", id)))
+            }
+            Ok(id) => {
+                valid.push(index);
+                ids.push(id);
+            }
+            Err(error) => invalid.push((index, error.to_string())),
+        }
+    }
+
+    let outcome = if invalid.is_empty() || bulk.mode == BulkMode::BestEffort {
+        Some(
+            respository::servers::delete_many(
+                &state.pool,
+                current_user.user_id,
+                ids,
+                bulk.mode,
+                bulk.force,
+            )
+            .await?,
+        )
+    } else {
+        None
+    };
+
+    let report = adapt_bulk_outcome(bulk.mode, BulkItemStatus::Deleted, invalid, valid, outcome);
+    let status = if report.committed {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    Ok((status, Json(report)))
+}
@@ -1,32 +1,63 @@
 use std::sync::Arc;
 
 use axum::{
-    body::Body, extract::{Query, State}, http::{HeaderName, HeaderValue, Response}, response::IntoResponse, Json
+    body::Body, extract::{Query, State}, http::{HeaderName, HeaderValue, Response}, response::IntoResponse, Extension, Json
 };
+use uuid::Uuid;
 
-use super::{CreateServerQuery, ServerResponse};
-use crate::infra::respository;
-use crate::{domains::models::server::ServerError, utils::JsonExtractor, AppState};
+use super::{adapt_server_to_server_response, pick_address, CreateServerQuery, NewServerAddress};
+use crate::infra::errors::InfraError;
+use crate::infra::{jobs::dns_check, middleware::auth_middleware::CurrentUser, respository};
+use crate::{
+    config::config, domains::models::server::ServerError, utils::JsonExtractor, AppState,
+};
 
 pub async fn create_server(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
     JsonExtractor(new_serser): JsonExtractor<CreateServerQuery>,
 ) -> Result<impl IntoResponse, ServerError> {
     tracing::info!("Creating a new server: {:?}", new_serser);
-    let new_server_db = respository::servers::NewServerDB {
-        ip: new_serser.ip,
+    let address =
+        pick_address(new_serser.ip, new_serser.subnet_id).map_err(ServerError::BadRequest)?;
+    let owner_id = current_user.user_id;
+    let uniqueness = config().await.address_uniqueness();
+    let new_server_db = move |ip| respository::servers::NewServerDB {
+        ip,
         name: new_serser.name,
+        owner_id,
+        labels: respository::servers::labels_to_json(new_serser.labels),
+        check_ports: respository::servers::ports_to_db(new_serser.check_ports),
+        metadata: respository::servers::metadata_to_json(new_serser.metadata),
+        hostname: new_serser.hostname,
     };
 
-    let created_server = respository::servers::insert(&state.pool, new_server_db)
+    let created_server = match address {
+        NewServerAddress::Literal(ip) => {
+            respository::servers::insert(&state.pool, new_server_db(ip), uniqueness).await?
+        }
+        NewServerAddress::Subnet(subnet_id) => respository::subnets::allocate(
+            &state.pool,
+            owner_id,
+            subnet_id,
+            uniqueness,
+            new_server_db,
+        )
         .await
-        .map_err(ServerError::InfraError)?;
-
-    let server_response = ServerResponse {
-        id: created_server.id.to_string(),
-        ip: created_server.ip,
-        name: created_server.name,
+        .map_err(|error| match error {
+            InfraError::NotFound => {
+                ServerError::BadRequest(format!("Subnet {} has not been found", subnet_id))
+            }
+            error => error.into(),
+        })?,
     };
+    dns_check::spawn_check(
+        state.pool.clone(),
+        state.resolver.clone(),
+        Uuid::parse_str(&created_server.id).into_iter().collect(),
+    );
+
+    let server_response = adapt_server_to_server_response(created_server);
 
     let response = Response::builder()
         .header("HX-Trigger", "AddServerDone")
@@ -2,20 +2,26 @@ use std::sync::Arc;
 
 use axum::{
     extract::{Path, State},
-    Json,
+    Extension,
 };
 use uuid::Uuid;
 
-use crate::{domains::models::server::ServerError, infra::respository, AppState};
+use crate::{
+    domains::models::server::ServerError,
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::DeleteServerQuery;
 
 pub async fn delete_server(
     State(state): State<Arc<AppState>>,
     Path(server_id): Path<Uuid>,
+    QueryExtractor(query): QueryExtractor<DeleteServerQuery>,
+    Extension(current_user): Extension<CurrentUser>,
 ) -> Result<(), ServerError> {
-    let delete_id = server_id;
-    let _ = respository::servers::delete(&state.pool, delete_id)
-        .await
-        .map_err(ServerError::InfraError)?;
+    respository::servers::delete(&state.pool, current_user.user_id, server_id, query.force).await?;
 
-    return Ok(());
+    Ok(())
 }
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::models::{server::ServerError, server_history::diff_servers},
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{ServerHistoryDiffQuery, ServerHistoryDiffResponse};
+
+/// Field-by-field difference between the server state at two revisions.
+pub async fn diff_server_history(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    QueryExtractor(query): QueryExtractor<ServerHistoryDiffQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ServerHistoryDiffResponse>, ServerError> {
+    let revisions = respository::server_history::get_revisions(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        Some(vec![query.from, query.to]),
+    )
+    .await?;
+
+    let find = |number: i32| {
+        revisions
+            .iter()
+            .find(|revision| revision.revision == number)
+            .ok_or(ServerError::NotFound)
+    };
+    let from = find(query.from)?;
+    let to = find(query.to)?;
+
+    Ok(Json(ServerHistoryDiffResponse {
+        server_id: server_id.to_string(),
+        from: query.from,
+        to: query.to,
+        changes: diff_servers(from.after.as_ref(), to.after.as_ref()),
+    }))
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use uuid::Uuid;
+
+use crate::{
+    domains::models::server::{format_server_address, ServerError},
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{self, servers::ServersFilter},
+    },
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{DnsMismatchResponse, DnsMismatchesResponse};
+
+/// Servers matching the `list_servers` filters whose hostname does not resolve
+/// to their address, or whose address has no PTR record naming the hostname.
+/// Checks made before the last change of address or hostname are ignored.
+pub async fn dns_mismatches(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<DnsMismatchesResponse>, ServerError> {
+    let servers =
+        respository::servers::get_all_matching(&state.pool, current_user.user_id, filter).await?;
+    let server_ids = servers
+        .iter()
+        .filter_map(|server| Uuid::parse_str(&server.id).ok())
+        .collect();
+    let mut checks = respository::server_dns::get_latest(&state.pool, server_ids).await?;
+
+    let servers = servers
+        .into_iter()
+        .filter_map(|server| {
+            let check = Uuid::parse_str(&server.id)
+                .ok()
+                .and_then(|id| checks.remove(&id))
+                .filter(|check| check.is_current(&server))?;
+            let issues = check.issues();
+            if issues.is_empty() {
+                return None;
+            }
+            Some(DnsMismatchResponse {
+                server_id: server.id,
+                name: server.name,
+                ip: format_server_address(&server.ip),
+                hostname: server.hostname,
+                issues,
+                addresses: check.addresses.iter().map(ToString::to_string).collect(),
+                forward_error: check.forward_error,
+                ptr_names: check.ptr_names,
+                reverse_error: check.reverse_error,
+                checked_at: check.checked_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(DnsMismatchesResponse { servers }))
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    domains::models::server::{duplicate_addresses, format_server_address, ServerError},
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{self, servers::ServersFilter},
+    },
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{adapt_server_to_server_response, DuplicateAddressResponse, DuplicateServersResponse};
+
+/// Addresses shared by several of the caller's servers matching the
+/// `list_servers` filters, left over from before addresses had to be unique.
+pub async fn duplicate_servers(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<DuplicateServersResponse>, ServerError> {
+    let servers =
+        respository::servers::get_all_matching(&state.pool, current_user.user_id, filter).await?;
+
+    let duplicates = duplicate_addresses(servers)
+        .into_iter()
+        .map(|(ip, servers)| DuplicateAddressResponse {
+            ip: format_server_address(&ip),
+            servers: servers
+                .into_iter()
+                .map(adapt_server_to_server_response)
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(DuplicateServersResponse { duplicates }))
+}
@@ -0,0 +1,73 @@
+use std::{io, sync::Arc};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    response::IntoResponse,
+    Extension,
+};
+
+use crate::{
+    domains::models::server_csv::servers_to_csv,
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{
+            self,
+            servers::{ServersFilter, ServersPagination},
+        },
+    },
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::ExportServersQuery;
+
+/// Streams every server matching the `list_servers` filters as CSV, one
+/// listing page at a time.
+pub async fn export_servers(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    QueryExtractor(query): QueryExtractor<ExportServersQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> impl IntoResponse {
+    let owner_id = current_user.user_id;
+    let sort = query.sort;
+
+    let pages = futures::stream::unfold(
+        (Some(ServersPagination::walk(sort)), true),
+        move |(pagination, first)| {
+            let state = state.clone();
+            let filter = filter.clone();
+            async move {
+                let page =
+                    respository::servers::get_all(&state.pool, owner_id, filter, pagination?)
+                        .await
+                        .map_err(|err| err.to_string())
+                        .and_then(|page| {
+                            let next = page
+                                .next_cursor
+                                .as_deref()
+                                .and_then(|cursor| ServersPagination::walk(sort).next_page(cursor));
+                            Ok((servers_to_csv(&page.servers, first)?, next))
+                        });
+
+                match page {
+                    Ok((csv, next)) => Some((Ok(csv), (next, false))),
+                    Err(err) => {
+                        tracing::error!("CSV export failed: {}", err);
+                        Some((Err(io::Error::other(err)), (None, false)))
+                    }
+                }
+            }
+        },
+    );
+
+    (
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (CONTENT_DISPOSITION, "attachment; filename=\"servers.csv\""),
+        ],
+        Body::from_stream(pages),
+    )
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::{extract::State, http::header::CONTENT_TYPE, response::IntoResponse, Extension};
+
+use crate::{
+    domains::models::{
+        inventory::{inventory_hosts, ssh_config},
+        server::ServerError,
+    },
+    infra::{
+        middleware::auth_middleware::CurrentUser,
+        respository::{self, servers::ServersFilter},
+    },
+    utils::QueryExtractor,
+    AppState,
+};
+
+/// OpenSSH client config for the servers matching the `list_servers` filters.
+/// `ssh.user`, `ssh.port` and `ssh.jump` metadata fill in `User`, `Port` and `ProxyJump`.
+pub async fn export_ssh_config(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(filter): QueryExtractor<ServersFilter>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, ServerError> {
+    let servers =
+        respository::servers::get_all_matching(&state.pool, current_user.user_id, filter).await?;
+
+    Ok((
+        [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+        ssh_config(&inventory_hosts(servers)),
+    ))
+}
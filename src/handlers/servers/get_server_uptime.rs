@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    config::config,
+    domains::models::server::ServerError,
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{OutageResponse, ServerUptimeQuery, ServerUptimeResponse};
+
+pub async fn get_server_uptime(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    QueryExtractor(params): QueryExtractor<ServerUptimeQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ServerUptimeResponse>, ServerError> {
+    let report = respository::uptime::get_uptime(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        params.window,
+        config().await.uptime_raw_retention(),
+    )
+    .await?;
+
+    let uptime_percent = report.uptime_percent();
+    Ok(Json(ServerUptimeResponse {
+        server_id: server_id.to_string(),
+        window: params.window.to_string(),
+        from: report.from,
+        to: report.to,
+        samples: report.samples,
+        uptime_percent,
+        latency_p50_ms: report.latency_p50_ms,
+        latency_p95_ms: report.latency_p95_ms,
+        downsampled: report.downsampled,
+        outages: report
+            .outages
+            .into_iter()
+            .map(|outage| OutageResponse {
+                started_at: outage.started_at,
+                ended_at: outage.ended_at,
+                duration_secs: (outage.ended_at.unwrap_or(report.to) - outage.started_at)
+                    .num_seconds(),
+            })
+            .collect(),
+    }))
+}
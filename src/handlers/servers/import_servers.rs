@@ -0,0 +1,136 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::{Multipart, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+
+use super::{
+    adapt_server_to_server_response, ImportRowResult, ImportRowStatus, ImportServersQuery,
+    ImportServersResponse,
+};
+use crate::config::config;
+use crate::domains::models::metadata::Metadata;
+use crate::domains::models::server_csv::parse_server_csv;
+use crate::infra::respository::servers::NewServerDB;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{domains::models::server::ServerError, utils::QueryExtractor, AppState};
+
+/// Upper bound on the number of data rows in one import file.
+const MAX_IMPORT_ROWS: usize = 5000;
+
+/// Imports a CSV inventory sent as the `file` part of a multipart form. An
+/// optional `mapping` part maps CSV headers to fields, e.g.
+/// `{"Hostname": "name", "Address": "ip", "Env": "labels.env"}`. Rows are
+/// matched to existing servers by name: unknown names are created, known ones
+/// get their ip and labels replaced.
+pub async fn import_servers(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    QueryExtractor(query): QueryExtractor<ImportServersQuery>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, ServerError> {
+    let bad_request =
+        |err: axum::extract::multipart::MultipartError| ServerError::BadRequest(err.body_text());
+
+    let mut file = None;
+    let mut mapping = HashMap::new();
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        match field.name() {
+            Some("file") => file = Some(field.bytes().await.map_err(bad_request)?),
+            Some("mapping") => {
+                let text = field.text().await.map_err(bad_request)?;
+                if !text.trim().is_empty() {
+                    mapping = serde_json::from_str(&text).map_err(|err| {
+                        ServerError::BadRequest(format!("`mapping` is not a JSON object: {}", err))
+                    })?;
+                }
+            }
+            _ => {}
+        }
+    }
+    let file = file.ok_or_else(|| ServerError::BadRequest(String::from("Missing `file` part")))?;
+
+    let import = parse_server_csv(&file, &mapping).map_err(ServerError::BadRequest)?;
+    if import.rows.len() > MAX_IMPORT_ROWS {
+        return Err(ServerError::BadRequest(format!(
+            "An import takes at most {} rows, got {}",
+            MAX_IMPORT_ROWS,
+            import.rows.len()
+        )));
+    }
+    tracing::info!(
+        "Importing {} server rows, dry run: {}",
+        import.rows.len(),
+        query.dry_run
+    );
+
+    let mut rows = Vec::new();
+    let mut lines = Vec::new();
+    let mut new_servers = Vec::new();
+    for (line, row) in import.rows {
+        match row {
+            Ok(row) => {
+                lines.push(line);
+                new_servers.push(NewServerDB {
+                    name: row.name,
+                    ip: row.ip,
+                    owner_id: current_user.user_id,
+                    labels: respository::servers::labels_to_json(row.labels),
+                    check_ports: Vec::new(),
+                    metadata: respository::servers::metadata_to_json(Metadata::new()),
+                    hostname: None,
+                });
+            }
+            Err(error) => rows.push(ImportRowResult {
+                line,
+                status: ImportRowStatus::Invalid,
+                server: None,
+                error: Some(error),
+            }),
+        }
+    }
+
+    let results = respository::servers::upsert_by_name(
+        &state.pool,
+        new_servers,
+        query.dry_run,
+        config().await.address_uniqueness(),
+    )
+    .await?;
+
+    rows.extend(
+        lines
+            .into_iter()
+            .zip(results)
+            .map(|(line, result)| match result {
+                Ok((action, server)) => ImportRowResult {
+                    line,
+                    status: action.into(),
+                    server: Some(adapt_server_to_server_response(server)),
+                    error: None,
+                },
+                Err(error) => ImportRowResult {
+                    line,
+                    status: ImportRowStatus::Failed,
+                    server: None,
+                    error: Some(error.to_string()),
+                },
+            }),
+    );
+    rows.sort_by_key(|row| row.line);
+
+    let count = |status: ImportRowStatus| rows.iter().filter(|row| row.status == status).count();
+    let report = ImportServersResponse {
+        dry_run: query.dry_run,
+        created: count(ImportRowStatus::Created),
+        updated: count(ImportRowStatus::Updated),
+        unchanged: count(ImportRowStatus::Unchanged),
+        failed: count(ImportRowStatus::Invalid) + count(ImportRowStatus::Failed),
+        ignored_columns: import.ignored_columns,
+        rows,
+    };
+
+    Ok(([("HX-Trigger", "AddServerDone")], Json(report)))
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    domains::models::server::ServerError,
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    AppState,
+};
+
+use super::{adapt_revision_to_revision_response, ServerHistoryResponse};
+
+pub async fn list_server_history(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ServerHistoryResponse>, ServerError> {
+    let revisions =
+        respository::server_history::get_history(&state.pool, current_user.user_id, server_id)
+            .await?;
+
+    Ok(Json(ServerHistoryResponse {
+        server_id: server_id.to_string(),
+        revisions: revisions
+            .into_iter()
+            .map(adapt_revision_to_revision_response)
+            .collect(),
+    }))
+}
@@ -1,50 +1,94 @@
 use std::sync::Arc;
 
-use axum::{
-    extract::{Query, State},
-    http::Extensions,
-    Extension, Json,
-};
+use axum::{extract::State, http::Extensions, Extension, Json};
+use uuid::Uuid;
 
 use crate::{
-    domains::models::server::{ServerError, ServerModel},
+    domains::models::server::ServerError,
     infra::{
+        errors::InfraError,
         middleware::auth_middleware::CurrentUser,
-        respository::{self, servers::ServersFilter},
+        respository::{
+            self,
+            servers::{ServersFilter, ServersPage, ServersPagination},
+        },
     },
+    utils::QueryExtractor,
     AppState,
 };
 
-use super::{ListServersResponse, ServerResponse};
+use super::{
+    adapt_server_to_server_response, ListServersResponse, ServerInclude, ServerResponse,
+    ServersIncludeQuery, ServersViewQuery,
+};
 
+/// Servers the caller pinned come first, e.g. `?view=<id>` lists the servers
+/// matching a saved view, which takes no further filter parameters.
 pub async fn list_servers(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ServersFilter>,
+    QueryExtractor(query): QueryExtractor<ServersFilter>,
+    QueryExtractor(pagination): QueryExtractor<ServersPagination>,
+    QueryExtractor(include): QueryExtractor<ServersIncludeQuery>,
+    QueryExtractor(view): QueryExtractor<ServersViewQuery>,
     Extension(current_user): Extension<CurrentUser>,
 ) -> Result<Json<ListServersResponse>, ServerError> {
     tracing::info!("list servers : {:?}", current_user);
-    let servers = respository::servers::get_all(&state.pool, query)
-        .await
-        .map_err(|_| ServerError::InternalServerError)?;
+    let query = match view.view {
+        Some(_) if !query.is_empty() => {
+            return Err(ServerError::BadRequest(String::from(
+                "`view` cannot be combined with filter parameters, save them in a view instead",
+            )))
+        }
+        Some(view_id) => {
+            let view = respository::saved_views::get(&state.pool, current_user.user_id, view_id)
+                .await
+                .map_err(|error| match error {
+                    InfraError::NotFound => {
+                        ServerError::BadRequest(format!("There is no saved view `{}`", view_id))
+                    }
+                    error => ServerError::from(error),
+                })?;
+            ServersFilter::from_params(&view.filter).map_err(ServerError::BadRequest)?
+        }
+        None => query,
+    };
+    let mut page = respository::servers::get_all(
+        &state.pool,
+        current_user.user_id,
+        query,
+        pagination.pinned_first(),
+    )
+    .await?;
+
+    if include.includes(ServerInclude::Services) {
+        let server_ids = page
+            .servers
+            .iter()
+            .filter_map(|server| Uuid::parse_str(&server.id).ok())
+            .collect();
+        let mut services = respository::services::get_for_servers(&state.pool, server_ids).await?;
+        for server in &mut page.servers {
+            server.services = Some(
+                Uuid::parse_str(&server.id)
+                    .ok()
+                    .and_then(|id| services.remove(&id))
+                    .unwrap_or_default(),
+            );
+        }
+    }
 
-    Ok(Json(adapt_servers_to_list_servers_response(servers)))
+    Ok(Json(adapt_servers_to_list_servers_response(page)))
 }
 
-fn adapt_servers_to_list_servers_response(servers: Vec<ServerModel>) -> ListServersResponse {
-    let servers_response: Vec<ServerResponse> = servers
+fn adapt_servers_to_list_servers_response(page: ServersPage) -> ListServersResponse {
+    let servers_response: Vec<ServerResponse> = page
+        .servers
         .into_iter()
         .map(adapt_server_to_server_response)
         .collect();
 
     ListServersResponse {
         servers: servers_response,
-    }
-}
-
-fn adapt_server_to_server_response(server: ServerModel) -> ServerResponse {
-    ServerResponse {
-        id: server.id.to_string(),
-        ip: server.ip,
-        name: server.name,
+        next_cursor: page.next_cursor,
     }
 }
@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    domains::models::server::ServerError,
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    AppState,
+};
+
+use super::{adapt_server_to_server_response, ListServersResponse};
+
+pub async fn list_trash(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListServersResponse>, ServerError> {
+    let servers = respository::servers::get_trash(&state.pool, current_user.user_id).await?;
+
+    Ok(Json(ListServersResponse {
+        servers: servers
+            .into_iter()
+            .map(adapt_server_to_server_response)
+            .collect(),
+        next_cursor: None,
+    }))
+}
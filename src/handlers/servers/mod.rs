@@ -1,16 +1,81 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::de::{self, IntoDeserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
+use crate::domains::models::labels::{deserialize_labels, deserialize_optional_labels, Labels};
+use crate::domains::models::metadata::Metadata;
+use crate::domains::models::server_history::{
+    FieldChange, ServerChangeAction, ServerRevisionModel,
+};
+use crate::domains::models::server::{
+    deserialize_check_ports, deserialize_optional_check_ports,
+    deserialize_optional_server_address, format_server_address,
+    ServerError, ServerModel,
+};
+use crate::domains::models::server_check::ServerStatus;
+use crate::domains::models::server_dns::{
+    deserialize_hostname_change, deserialize_optional_hostname, DnsIssue,
+};
+use crate::domains::models::uptime::{deserialize_uptime_window, UptimeWindow};
+use crate::handlers::services::{adapt_service_to_service_response, ServiceResponse};
+use crate::infra::errors::InfraError;
+use crate::infra::respository::servers::{BulkMode, BulkOutcome, ImportAction, ServersSort};
+
+pub mod bulk_create_servers;
+pub mod bulk_delete_servers;
 pub mod create_server;
 pub mod list_servers;
 pub mod delete_server;
+pub mod diff_server_history;
+pub mod dns_mismatches;
+pub mod duplicate_servers;
+pub mod export_servers;
+pub mod export_ssh_config;
+pub mod get_server_uptime;
+pub mod import_servers;
+pub mod list_server_history;
+pub mod list_trash;
+pub mod restore_server;
+pub mod suggest_servers;
+pub mod update_server;
 
 // req & res
 
+/// Takes either a literal `ip` or a `subnet_id` to allocate the next free
+/// address from.
 #[derive(Deserialize, Debug)]
 pub struct CreateServerQuery {
-    ip: String,
+    #[serde(default, deserialize_with = "deserialize_optional_server_address")]
+    ip: Option<IpNetwork>,
+    subnet_id: Option<Uuid>,
     name: String,
+    #[serde(default, deserialize_with = "deserialize_labels")]
+    labels: Labels,
+    #[serde(default, deserialize_with = "deserialize_check_ports")]
+    check_ports: Vec<u16>,
+    #[serde(default)]
+    metadata: Metadata,
+    #[serde(default, deserialize_with = "deserialize_optional_hostname")]
+    hostname: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateServerQuery {
+    #[serde(default, deserialize_with = "deserialize_optional_server_address")]
+    ip: Option<IpNetwork>,
+    name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_labels")]
+    labels: Option<Labels>,
+    #[serde(default, deserialize_with = "deserialize_optional_check_ports")]
+    check_ports: Option<Vec<u16>>,
+    /// Replaces the whole metadata object.
+    metadata: Option<Metadata>,
+    /// An empty string clears the hostname.
+    #[serde(default, deserialize_with = "deserialize_hostname_change")]
+    hostname: Option<Option<String>>,
+    version: i32,
 }
 
 #[derive(Serialize, Debug, Deserialize)]
@@ -18,14 +83,471 @@ pub struct ServerResponse {
     id:String,
     ip: String,
     name: String,
+    hostname: Option<String>,
+    version: i32,
+    labels: Labels,
+    created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_at: Option<DateTime<Utc>>,
+    check_ports: Vec<u16>,
+    metadata: Metadata,
+    /// Health as of the last check; only filled in by listings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<ServerStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    services: Option<Vec<ServiceResponse>>,
+    /// Whether a maintenance window is in effect; only filled in by listings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_maintenance: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maintenance_ends_at: Option<DateTime<Utc>>,
+    /// Whether the caller pinned the server; only filled in by listings.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pinned: Option<bool>,
+}
+
+/// Related resources a listing can embed, e.g. `include=services`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerInclude {
+    Services,
+}
+
+/// A saved view whose filter replaces the filter parameters of the request.
+#[derive(Deserialize, Debug, Default)]
+pub struct ServersViewQuery {
+    view: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ServersIncludeQuery {
+    #[serde(default, deserialize_with = "deserialize_includes")]
+    include: Vec<ServerInclude>,
+}
+
+impl ServersIncludeQuery {
+    fn includes(&self, include: ServerInclude) -> bool {
+        self.include.contains(&include)
+    }
+}
+
+/// Comma separated, e.g. `include=services`.
+fn deserialize_includes<'de, D>(deserializer: D) -> Result<Vec<ServerInclude>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .split(',')
+        .map(str::trim)
+        .filter(|include| !include.is_empty())
+        .map(|include| {
+            ServerInclude::deserialize(include.into_deserializer())
+                .map_err(|_: de::value::Error| {
+                    de::Error::custom(format!("`{}` cannot be included, use services", include))
+                })
+        })
+        .collect()
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListServersResponse {
     servers: Vec<ServerResponse>,
+    next_cursor: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SuggestServersQuery {
+    #[serde(default)]
+    q: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerSuggestion {
+    id: String,
+    name: String,
+    ip: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SuggestServersResponse {
+    suggestions: Vec<ServerSuggestion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerRevisionResponse {
+    revision: i32,
+    action: ServerChangeAction,
+    actor_id: Option<String>,
+    actor: Option<String>,
+    changed_at: DateTime<Utc>,
+    before: Option<ServerResponse>,
+    after: Option<ServerResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerHistoryResponse {
+    server_id: String,
+    revisions: Vec<ServerRevisionResponse>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ServerHistoryDiffQuery {
+    from: i32,
+    to: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerHistoryDiffResponse {
+    server_id: String,
+    from: i32,
+    to: i32,
+    changes: Vec<FieldChange>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ServerUptimeQuery {
+    #[serde(default, deserialize_with = "deserialize_uptime_window")]
+    window: UptimeWindow,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OutageResponse {
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    /// Up to now for an ongoing outage.
+    duration_secs: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerUptimeResponse {
+    server_id: String,
+    window: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    samples: i64,
+    /// `null` when the server was not checked during the window.
+    uptime_percent: Option<f64>,
+    latency_p50_ms: Option<i32>,
+    latency_p95_ms: Option<i32>,
+    downsampled: bool,
+    outages: Vec<OutageResponse>,
+}
+
+/// A server whose last DNS check disagrees with its recorded address.
+#[derive(Debug, Serialize)]
+pub struct DnsMismatchResponse {
+    server_id: String,
+    name: String,
+    ip: String,
+    hostname: Option<String>,
+    issues: Vec<DnsIssue>,
+    /// What `hostname` resolved to.
+    addresses: Vec<String>,
+    forward_error: Option<String>,
+    /// PTR records of `ip`.
+    ptr_names: Vec<String>,
+    reverse_error: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DnsMismatchesResponse {
+    servers: Vec<DnsMismatchResponse>,
+}
+
+/// Live servers sharing one address.
+#[derive(Debug, Serialize)]
+pub struct DuplicateAddressResponse {
+    ip: String,
+    servers: Vec<ServerResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateServersResponse {
+    duplicates: Vec<DuplicateAddressResponse>,
+}
+
+/// Upper bound on the number of items in one bulk request.
+const MAX_BULK_ITEMS: usize = 500;
+
+/// Items are kept as raw JSON so one malformed entry is reported against its
+/// index instead of rejecting the whole body.
+#[derive(Deserialize, Debug)]
+pub struct BulkCreateServersQuery {
+    #[serde(default)]
+    mode: BulkMode,
+    servers: Vec<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BulkDeleteServersQuery {
+    #[serde(default)]
+    mode: BulkMode,
+    #[serde(default)]
+    force: bool,
+    ids: Vec<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkItemStatus {
+    Created,
+    Deleted,
+    /// Rejected by validation, never sent to the database.
+    Invalid,
+    Failed,
+    /// Valid, but not applied because the batch was aborted.
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult {
+    index: usize,
+    status: BulkItemStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server: Option<ServerResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkServersResponse {
+    mode: BulkMode,
+    committed: bool,
+    succeeded: usize,
+    failed: usize,
+    results: Vec<BulkItemResult>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ExportServersQuery {
+    sort: Option<ServersSort>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ImportServersQuery {
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRowStatus {
+    Created,
+    Updated,
+    Unchanged,
+    Invalid,
+    Failed,
+}
+
+impl From<ImportAction> for ImportRowStatus {
+    fn from(action: ImportAction) -> Self {
+        match action {
+            ImportAction::Created => ImportRowStatus::Created,
+            ImportAction::Updated => ImportRowStatus::Updated,
+            ImportAction::Unchanged => ImportRowStatus::Unchanged,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportRowResult {
+    line: u64,
+    status: ImportRowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    server: Option<ServerResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportServersResponse {
+    dry_run: bool,
+    created: usize,
+    updated: usize,
+    unchanged: usize,
+    failed: usize,
+    ignored_columns: Vec<String>,
+    rows: Vec<ImportRowResult>,
+}
+
+/// Without `force`, deleting a server other live servers depend on is refused.
 #[derive(Deserialize)]
 pub struct DeleteServerQuery {
-    id: Uuid,
-}
\ No newline at end of file
+    #[serde(default)]
+    force: bool,
+}
+
+pub fn adapt_server_to_server_response(server: ServerModel) -> ServerResponse {
+    ServerResponse {
+        id: server.id,
+        ip: format_server_address(&server.ip),
+        name: server.name,
+        hostname: server.hostname,
+        version: server.version,
+        labels: server.labels,
+        created_at: server.created_at,
+        deleted_at: server.deleted_at,
+        check_ports: server.check_ports,
+        metadata: server.metadata,
+        status: server.check.as_ref().map(|check| check.status),
+        latency_ms: server.check.as_ref().and_then(|check| check.latency_ms),
+        last_checked_at: server.check.as_ref().map(|check| check.checked_at),
+        services: server.services.map(|services| {
+            services
+                .into_iter()
+                .map(adapt_service_to_service_response)
+                .collect()
+        }),
+        in_maintenance: server.maintenance.map(|occurrence| occurrence.is_some()),
+        maintenance_ends_at: server.maintenance.flatten().map(|occurrence| occurrence.ends_at),
+        pinned: server.pinned,
+    }
+}
+
+fn adapt_revision_to_revision_response(revision: ServerRevisionModel) -> ServerRevisionResponse {
+    ServerRevisionResponse {
+        revision: revision.revision,
+        action: revision.action,
+        actor_id: revision.actor_id.map(|actor_id| actor_id.to_string()),
+        actor: revision.actor_username,
+        changed_at: revision.changed_at,
+        before: revision.before.map(adapt_server_to_server_response),
+        after: revision.after.map(adapt_server_to_server_response),
+    }
+}
+
+fn check_bulk_size(items: usize) -> Result<(), ServerError> {
+    if items > MAX_BULK_ITEMS {
+        return Err(ServerError::BadRequest(format!(
+            "A bulk request takes at most {} items, got {}",
+            MAX_BULK_ITEMS, items
+        )));
+    }
+    Ok(())
+}
+
+/// Where the address of a new server comes from.
+enum NewServerAddress {
+    Literal(IpNetwork),
+    Subnet(Uuid),
+}
+
+fn pick_address(
+    ip: Option<IpNetwork>,
+    subnet_id: Option<Uuid>,
+) -> Result<NewServerAddress, String> {
+    match (ip, subnet_id) {
+        (Some(ip), None) => Ok(NewServerAddress::Literal(ip)),
+        (None, Some(subnet_id)) => Ok(NewServerAddress::Subnet(subnet_id)),
+        (Some(_), Some(_)) => Err(String::from("`ip` and `subnet_id` cannot be combined")),
+        (None, None) => Err(String::from("A server needs either an `ip` or a `subnet_id`")),
+    }
+}
+
+fn describe_bulk_error(error: InfraError) -> String {
+    match error {
+        InfraError::NotFound => String::from("Server not found"),
+        InfraError::Conflict => String::from("Server has been modified since it was read"),
+        InfraError::UniqueViolation(_) => error.to_string(),
+        InfraError::InternalServerError => String::from("Internal server error"),
+        InfraError::Invalid(message)
+        | InfraError::Duplicate(message)
+        | InfraError::InUse(message) => message,
+    }
+}
+
+/// Builds the per-item report from the items rejected by validation and the
+/// repository outcome for the rest. `valid` maps the position of every item
+/// handed to the repository back to its index in the request. `outcome` is
+/// `None` when validation aborted the batch before it reached the database.
+fn adapt_bulk_outcome(
+    mode: BulkMode,
+    applied: BulkItemStatus,
+    invalid: Vec<(usize, String)>,
+    valid: Vec<usize>,
+    outcome: Option<BulkOutcome<ServerModel>>,
+) -> BulkServersResponse {
+    let mut results: Vec<BulkItemResult> = invalid
+        .into_iter()
+        .map(|(index, error)| BulkItemResult {
+            index,
+            status: BulkItemStatus::Invalid,
+            server: None,
+            error: Some(error),
+        })
+        .collect();
+
+    let committed = matches!(outcome, Some(BulkOutcome::Committed(_)));
+    match outcome {
+        Some(BulkOutcome::Committed(items)) => {
+            results.extend(valid.into_iter().zip(items).map(|(index, item)| match item {
+                Ok(server) => BulkItemResult {
+                    index,
+                    status: applied,
+                    server: Some(adapt_server_to_server_response(server)),
+                    error: None,
+                },
+                Err(error) => BulkItemResult {
+                    index,
+                    status: BulkItemStatus::Failed,
+                    server: None,
+                    error: Some(describe_bulk_error(error)),
+                },
+            }));
+        }
+        Some(BulkOutcome::Aborted {
+            index: failed,
+            error,
+        }) => {
+            let mut error = Some(error);
+            results.extend(valid.into_iter().enumerate().map(|(position, index)| {
+                if position == failed {
+                    BulkItemResult {
+                        index,
+                        status: BulkItemStatus::Failed,
+                        server: None,
+                        error: error.take().map(describe_bulk_error),
+                    }
+                } else {
+                    BulkItemResult {
+                        index,
+                        status: BulkItemStatus::Skipped,
+                        server: None,
+                        error: None,
+                    }
+                }
+            }));
+        }
+        None => {
+            results.extend(valid.into_iter().map(|index| BulkItemResult {
+                index,
+                status: BulkItemStatus::Skipped,
+                server: None,
+                error: None,
+            }));
+        }
+    }
+    results.sort_by_key(|result| result.index);
+
+    let succeeded = results
+        .iter()
+        .filter(|result| result.status == applied)
+        .count();
+    let failed = results
+        .iter()
+        .filter(|result| matches!(result.status, BulkItemStatus::Invalid | BulkItemStatus::Failed))
+        .count();
+
+    BulkServersResponse {
+        mode,
+        committed,
+        succeeded,
+        failed,
+        results,
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::adapt_server_to_server_response;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{config::config, domains::models::server::ServerError, AppState};
+
+pub async fn restore_server(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<impl IntoResponse, ServerError> {
+    let restored_server = respository::servers::restore(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        config().await.address_uniqueness(),
+    )
+    .await?;
+
+    Ok((
+        [("HX-Trigger", "RestoreServerDone")],
+        Json(adapt_server_to_server_response(restored_server)),
+    ))
+}
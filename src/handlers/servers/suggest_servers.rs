@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use crate::{
+    domains::models::server::{format_server_address, ServerError, ServerModel},
+    infra::{middleware::auth_middleware::CurrentUser, respository},
+    utils::QueryExtractor,
+    AppState,
+};
+
+use super::{ServerSuggestion, SuggestServersQuery, SuggestServersResponse};
+
+const SUGGESTIONS_LIMIT: i64 = 10;
+
+pub async fn suggest_servers(
+    State(state): State<Arc<AppState>>,
+    QueryExtractor(query): QueryExtractor<SuggestServersQuery>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<SuggestServersResponse>, ServerError> {
+    if query.q.trim().is_empty() {
+        return Ok(Json(SuggestServersResponse {
+            suggestions: Vec::new(),
+        }));
+    }
+
+    let servers = respository::servers::suggest(
+        &state.pool,
+        current_user.user_id,
+        query.q,
+        SUGGESTIONS_LIMIT,
+    )
+    .await?;
+
+    Ok(Json(SuggestServersResponse {
+        suggestions: servers
+            .into_iter()
+            .map(adapt_server_to_suggestion)
+            .collect(),
+    }))
+}
+
+fn adapt_server_to_suggestion(server: ServerModel) -> ServerSuggestion {
+    ServerSuggestion {
+        id: server.id,
+        ip: format_server_address(&server.ip),
+        name: server.name,
+    }
+}
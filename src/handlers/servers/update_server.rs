@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_server_to_server_response, UpdateServerQuery};
+use crate::infra::{jobs::dns_check, middleware::auth_middleware::CurrentUser, respository};
+use crate::{config::config, domains::models::server::ServerError, utils::JsonExtractor, AppState};
+
+pub async fn update_server(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(changes): JsonExtractor<UpdateServerQuery>,
+) -> Result<impl IntoResponse, ServerError> {
+    tracing::info!("Updating server {}: {:?}", server_id, changes);
+    let dns_changed = changes.ip.is_some() || changes.hostname.is_some();
+    let update_server_db = respository::servers::UpdateServer {
+        ip: changes.ip,
+        name: changes.name,
+        labels: changes.labels.map(respository::servers::labels_to_json),
+        check_ports: changes.check_ports.map(respository::servers::ports_to_db),
+        metadata: changes.metadata.map(respository::servers::metadata_to_json),
+        hostname: changes.hostname,
+    };
+
+    let updated_server = respository::servers::update(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        changes.version,
+        update_server_db,
+        config().await.address_uniqueness(),
+    )
+    .await?;
+
+    if dns_changed {
+        dns_check::spawn_check(state.pool.clone(), state.resolver.clone(), vec![server_id]);
+    }
+
+    Ok((
+        [("HX-Trigger", "UpdateServerDone")],
+        Json(adapt_server_to_server_response(updated_server)),
+    ))
+}
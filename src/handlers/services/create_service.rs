@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{
+    adapt_service_to_service_response, parse_name, parse_team, parse_url, CreateServiceQuery,
+    ServiceResponse,
+};
+use crate::domains::models::service::ServiceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn create_service(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_service): JsonExtractor<CreateServiceQuery>,
+) -> Result<Json<ServiceResponse>, ServiceError> {
+    let new_service_db = respository::services::NewServiceDB {
+        id: Uuid::new_v4(),
+        server_id,
+        name: parse_name(&new_service.name)?,
+        port: i32::from(new_service.port),
+        protocol: new_service.protocol.as_str().to_string(),
+        team: match new_service.team {
+            Some(team) => parse_team(&team)?,
+            None => None,
+        },
+        url: match new_service.url {
+            Some(url) => parse_url(&url)?,
+            None => None,
+        },
+    };
+
+    let service =
+        respository::services::insert(&state.pool, current_user.user_id, new_service_db).await?;
+
+    Ok(Json(adapt_service_to_service_response(service)))
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::service::ServiceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_service(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, service_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), ServiceError> {
+    respository::services::delete(&state.pool, current_user.user_id, server_id, service_id).await?;
+
+    Ok(())
+}
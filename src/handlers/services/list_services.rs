@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{adapt_service_to_service_response, ListServicesResponse};
+use crate::domains::models::service::ServiceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_services(
+    State(state): State<Arc<AppState>>,
+    Path(server_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListServicesResponse>, ServiceError> {
+    let services =
+        respository::services::get_all(&state.pool, current_user.user_id, server_id).await?;
+
+    Ok(Json(ListServicesResponse {
+        server_id: server_id.to_string(),
+        services: services
+            .into_iter()
+            .map(adapt_service_to_service_response)
+            .collect(),
+    }))
+}
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::service::{
+    check_service_name, check_service_url, deserialize_optional_service_port,
+    deserialize_service_port, ServiceError, ServiceModel, ServiceProtocol,
+};
+
+pub mod create_service;
+pub mod delete_service;
+pub mod list_services;
+pub mod update_service;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct CreateServiceQuery {
+    name: String,
+    #[serde(deserialize_with = "deserialize_service_port")]
+    port: u16,
+    #[serde(default)]
+    protocol: ServiceProtocol,
+    team: Option<String>,
+    url: Option<String>,
+}
+
+/// Fields left out are kept; an empty `team` or `url` clears it.
+#[derive(Deserialize, Debug)]
+pub struct UpdateServiceQuery {
+    name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_service_port")]
+    port: Option<u16>,
+    protocol: Option<ServiceProtocol>,
+    team: Option<String>,
+    url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceResponse {
+    id: String,
+    name: String,
+    port: u16,
+    protocol: ServiceProtocol,
+    team: Option<String>,
+    url: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListServicesResponse {
+    server_id: String,
+    services: Vec<ServiceResponse>,
+}
+
+pub fn adapt_service_to_service_response(service: ServiceModel) -> ServiceResponse {
+    ServiceResponse {
+        id: service.id.to_string(),
+        name: service.name,
+        port: service.port,
+        protocol: service.protocol,
+        team: service.team,
+        url: service.url,
+        created_at: service.created_at,
+    }
+}
+
+fn parse_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    check_service_name(name).map_err(ServiceError::BadRequest)?;
+    Ok(name.to_string())
+}
+
+fn parse_team(team: &str) -> Result<Option<String>, ServiceError> {
+    let team = team.trim();
+    if team.len() > 255 {
+        return Err(ServiceError::BadRequest(String::from(
+            "A team name is at most 255 characters",
+        )));
+    }
+    Ok(Some(team.to_string()).filter(|team| !team.is_empty()))
+}
+
+fn parse_url(url: &str) -> Result<Option<String>, ServiceError> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    check_service_url(url).map_err(ServiceError::BadRequest)?;
+    Ok(Some(url.to_string()))
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::{
+    adapt_service_to_service_response, parse_name, parse_team, parse_url, ServiceResponse,
+    UpdateServiceQuery,
+};
+use crate::domains::models::service::ServiceError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn update_service(
+    State(state): State<Arc<AppState>>,
+    Path((server_id, service_id)): Path<(Uuid, Uuid)>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(changes): JsonExtractor<UpdateServiceQuery>,
+) -> Result<Json<ServiceResponse>, ServiceError> {
+    let update_service_db = respository::services::UpdateServiceDB {
+        name: changes.name.as_deref().map(parse_name).transpose()?,
+        port: changes.port.map(i32::from),
+        protocol: changes
+            .protocol
+            .map(|protocol| protocol.as_str().to_string()),
+        team: changes.team.as_deref().map(parse_team).transpose()?,
+        url: changes.url.as_deref().map(parse_url).transpose()?,
+    };
+    if update_service_db.is_empty() {
+        return Err(ServiceError::BadRequest(String::from(
+            "Nothing to update, send at least one of name, port, protocol, team or url",
+        )));
+    }
+
+    let service = respository::services::update(
+        &state.pool,
+        current_user.user_id,
+        server_id,
+        service_id,
+        update_service_db,
+    )
+    .await?;
+
+    Ok(Json(adapt_service_to_service_response(service)))
+}
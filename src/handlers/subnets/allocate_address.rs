@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    Extension, Json,
+};
+use uuid::Uuid;
+
+use super::AllocateAddressQuery;
+use crate::domains::models::subnet::SubnetError;
+use crate::handlers::servers::adapt_server_to_server_response;
+use crate::infra::respository::servers::NewServerDB;
+use crate::infra::{jobs::dns_check, middleware::auth_middleware::CurrentUser, respository};
+use crate::{config::config, utils::JsonExtractor, AppState};
+
+/// Creates a server on the next free address of the subnet.
+pub async fn allocate_address(
+    State(state): State<Arc<AppState>>,
+    Path(subnet_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_server): JsonExtractor<AllocateAddressQuery>,
+) -> Result<impl IntoResponse, SubnetError> {
+    let owner_id = current_user.user_id;
+    let created_server = respository::subnets::allocate(
+        &state.pool,
+        owner_id,
+        subnet_id,
+        config().await.address_uniqueness(),
+        move |ip| NewServerDB {
+            ip,
+            name: new_server.name,
+            owner_id,
+            labels: respository::servers::labels_to_json(new_server.labels),
+            check_ports: respository::servers::ports_to_db(new_server.check_ports),
+            metadata: respository::servers::metadata_to_json(new_server.metadata),
+            hostname: new_server.hostname,
+        },
+    )
+    .await?;
+    dns_check::spawn_check(
+        state.pool.clone(),
+        state.resolver.clone(),
+        Uuid::parse_str(&created_server.id).into_iter().collect(),
+    );
+
+    Ok((
+        [("HX-Trigger", "AddServerDone")],
+        Json(adapt_server_to_server_response(created_server)),
+    ))
+}
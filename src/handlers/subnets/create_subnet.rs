@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use super::{adapt_subnet_to_subnet_response, CreateSubnetQuery, SubnetResponse};
+use crate::domains::models::subnet::{check_description, check_gateway, check_vlan, SubnetError};
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn create_subnet(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_subnet): JsonExtractor<CreateSubnetQuery>,
+) -> Result<Json<SubnetResponse>, SubnetError> {
+    if let Some(gateway) = new_subnet.gateway {
+        check_gateway(&new_subnet.cidr, gateway).map_err(SubnetError::BadRequest)?;
+    }
+    if let Some(vlan) = new_subnet.vlan {
+        check_vlan(vlan).map_err(SubnetError::BadRequest)?;
+    }
+    let description = new_subnet
+        .description
+        .map(|description| description.trim().to_string())
+        .filter(|description| !description.is_empty());
+    if let Some(description) = &description {
+        check_description(description).map_err(SubnetError::BadRequest)?;
+    }
+
+    let new_subnet_db = respository::subnets::NewSubnetDB {
+        id: Uuid::new_v4(),
+        owner_id: current_user.user_id,
+        cidr: new_subnet.cidr,
+        gateway: new_subnet.gateway.map(IpNetwork::from),
+        vlan: new_subnet.vlan,
+        description,
+    };
+
+    let subnet = respository::subnets::insert(&state.pool, new_subnet_db).await?;
+    let usage = subnet.usage(&HashSet::new());
+
+    Ok(Json(adapt_subnet_to_subnet_response(subnet, usage)))
+}
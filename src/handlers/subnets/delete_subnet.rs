@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::subnet::SubnetError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_subnet(
+    State(state): State<Arc<AppState>>,
+    Path(subnet_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), SubnetError> {
+    respository::subnets::delete(&state.pool, current_user.user_id, subnet_id).await?;
+
+    Ok(())
+}
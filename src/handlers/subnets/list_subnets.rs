@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use super::{adapt_subnet_to_subnet_response, ListSubnetsResponse};
+use crate::domains::models::subnet::SubnetError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{config::config, AppState};
+
+pub async fn list_subnets(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListSubnetsResponse>, SubnetError> {
+    let subnets = respository::subnets::get_all(
+        &state.pool,
+        current_user.user_id,
+        config().await.address_uniqueness(),
+    )
+    .await?;
+
+    Ok(Json(ListSubnetsResponse {
+        subnets: subnets
+            .into_iter()
+            .map(|(subnet, usage)| adapt_subnet_to_subnet_response(subnet, usage))
+            .collect(),
+    }))
+}
@@ -0,0 +1,77 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use ipnetwork::IpNetwork;
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::labels::{deserialize_labels, Labels};
+use crate::domains::models::metadata::Metadata;
+use crate::domains::models::server::deserialize_check_ports;
+use crate::domains::models::server_dns::deserialize_optional_hostname;
+use crate::domains::models::subnet::{deserialize_cidr, SubnetModel, SubnetUsage};
+
+pub mod allocate_address;
+pub mod create_subnet;
+pub mod delete_subnet;
+pub mod list_subnets;
+
+// req & res
+
+#[derive(Deserialize, Debug)]
+pub struct CreateSubnetQuery {
+    #[serde(deserialize_with = "deserialize_cidr")]
+    cidr: IpNetwork,
+    gateway: Option<IpAddr>,
+    vlan: Option<i32>,
+    description: Option<String>,
+}
+
+/// The server to create on the allocated address, `CreateServerQuery` without `ip`.
+#[derive(Deserialize, Debug)]
+pub struct AllocateAddressQuery {
+    name: String,
+    #[serde(default, deserialize_with = "deserialize_labels")]
+    labels: Labels,
+    #[serde(default, deserialize_with = "deserialize_check_ports")]
+    check_ports: Vec<u16>,
+    #[serde(default)]
+    metadata: Metadata,
+    #[serde(default, deserialize_with = "deserialize_optional_hostname")]
+    hostname: Option<String>,
+}
+
+/// `capacity` saturates for IPv6 subnets larger than 2^64 addresses.
+#[derive(Debug, Serialize)]
+pub struct SubnetResponse {
+    id: String,
+    cidr: String,
+    gateway: Option<String>,
+    vlan: Option<i32>,
+    description: Option<String>,
+    created_at: DateTime<Utc>,
+    capacity: u64,
+    used: u64,
+    /// Percentage of `capacity` in use.
+    utilisation: f64,
+    next_free: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListSubnetsResponse {
+    subnets: Vec<SubnetResponse>,
+}
+
+fn adapt_subnet_to_subnet_response(subnet: SubnetModel, usage: SubnetUsage) -> SubnetResponse {
+    SubnetResponse {
+        id: subnet.id.to_string(),
+        cidr: subnet.cidr.to_string(),
+        gateway: subnet.gateway.map(|gateway| gateway.to_string()),
+        vlan: subnet.vlan,
+        description: subnet.description,
+        created_at: subnet.created_at,
+        capacity: u64::try_from(usage.capacity).unwrap_or(u64::MAX),
+        used: u64::try_from(usage.used).unwrap_or(u64::MAX),
+        utilisation: usage.utilisation(),
+        next_free: usage.next_free.map(|address| address.to_string()),
+    }
+}
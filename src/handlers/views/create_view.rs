@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+use uuid::Uuid;
+
+use super::{adapt_view_to_view_response, CreateViewQuery, ViewResponse};
+use crate::domains::models::saved_view::{check_view_name, ViewError};
+use crate::infra::respository::servers::ServersFilter;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::{utils::JsonExtractor, AppState};
+
+pub async fn create_view(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+    JsonExtractor(new_view): JsonExtractor<CreateViewQuery>,
+) -> Result<Json<ViewResponse>, ViewError> {
+    check_view_name(&new_view.name).map_err(ViewError::BadRequest)?;
+    ServersFilter::from_params(&new_view.filter).map_err(ViewError::BadRequest)?;
+
+    let new_view_db = respository::saved_views::NewSavedViewDB {
+        id: Uuid::new_v4(),
+        owner_id: current_user.user_id,
+        name: new_view.name.trim().to_string(),
+        filter: serde_json::json!(new_view.filter),
+    };
+
+    let view = respository::saved_views::insert(&state.pool, new_view_db).await?;
+
+    Ok(Json(adapt_view_to_view_response(view)))
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    Extension,
+};
+use uuid::Uuid;
+
+use crate::domains::models::saved_view::ViewError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn delete_view(
+    State(state): State<Arc<AppState>>,
+    Path(view_id): Path<Uuid>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<(), ViewError> {
+    respository::saved_views::delete(&state.pool, current_user.user_id, view_id).await?;
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use axum::{extract::State, Extension, Json};
+
+use super::{adapt_view_to_view_response, ListViewsResponse};
+use crate::domains::models::saved_view::ViewError;
+use crate::infra::{middleware::auth_middleware::CurrentUser, respository};
+use crate::AppState;
+
+pub async fn list_views(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<CurrentUser>,
+) -> Result<Json<ListViewsResponse>, ViewError> {
+    let views = respository::saved_views::get_all(&state.pool, current_user.user_id).await?;
+
+    Ok(Json(ListViewsResponse {
+        views: views.into_iter().map(adapt_view_to_view_response).collect(),
+    }))
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domains::models::saved_view::SavedViewModel;
+
+pub mod create_view;
+pub mod delete_view;
+pub mod list_views;
+
+// req & res
+
+/// `filter` takes the `list_servers` filter parameters, e.g.
+/// `{"name_contains": "web", "label_selector": "env=prod"}`.
+#[derive(Deserialize, Debug)]
+pub struct CreateViewQuery {
+    name: String,
+    #[serde(default)]
+    filter: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ViewResponse {
+    id: String,
+    name: String,
+    filter: BTreeMap<String, String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListViewsResponse {
+    views: Vec<ViewResponse>,
+}
+
+fn adapt_view_to_view_response(view: SavedViewModel) -> ViewResponse {
+    ViewResponse {
+        id: view.id.to_string(),
+        name: view.name,
+        filter: view.filter,
+        created_at: view.created_at,
+    }
+}
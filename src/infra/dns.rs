@@ -0,0 +1,282 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Looks hostnames and addresses up for the DNS checks.
+#[derive(Debug, Clone)]
+pub enum Resolver {
+    /// The system resolver (getaddrinfo/getnameinfo), so `/etc/hosts`,
+    /// nsswitch and `/etc/resolv.conf` all apply.
+    System { timeout: Duration },
+    /// Only the entries of a hosts(5) file, re-read on every lookup. Lets the
+    /// checks run against a stub in tests and sandboxes without DNS.
+    HostsFile(PathBuf),
+}
+
+impl Resolver {
+    pub fn new(hosts_file: Option<&str>, timeout: Duration) -> Self {
+        match hosts_file {
+            Some(path) => Resolver::HostsFile(PathBuf::from(path)),
+            None => Resolver::System { timeout },
+        }
+    }
+
+    /// Addresses of `hostname`, an error if it does not resolve.
+    pub async fn lookup_host(&self, hostname: &str) -> Result<Vec<IpAddr>, String> {
+        let mut addresses = match self {
+            Resolver::System { timeout } => {
+                let hostname = hostname.to_string();
+                blocking(*timeout, move || dns_lookup::lookup_host(&hostname))
+                    .await?
+                    .map_err(|err| err.to_string())?
+            }
+            Resolver::HostsFile(path) => read_hosts(path)
+                .await?
+                .into_iter()
+                .filter(|(_, names)| names.iter().any(|name| name.eq_ignore_ascii_case(hostname)))
+                .map(|(address, _)| address)
+                .collect(),
+        };
+        addresses.sort();
+        addresses.dedup();
+        if addresses.is_empty() {
+            return Err(format!("`{}` does not resolve", hostname));
+        }
+        Ok(addresses)
+    }
+
+    /// PTR names of `address`; none is not an error, many addresses have no PTR.
+    pub async fn lookup_addr(&self, address: IpAddr) -> Result<Vec<String>, String> {
+        match self {
+            Resolver::System { timeout } => {
+                match blocking(*timeout, move || dns_lookup::lookup_addr(&address)).await? {
+                    Ok(name) if name.parse::<IpAddr>().is_err() => Ok(vec![name.to_lowercase()]),
+                    // Without a PTR record getnameinfo fails because of NI_NAMEREQD.
+                    _ => Ok(Vec::new()),
+                }
+            }
+            Resolver::HostsFile(path) => Ok(read_hosts(path)
+                .await?
+                .into_iter()
+                .filter(|(entry, _)| *entry == address)
+                .filter_map(|(_, names)| names.into_iter().next())
+                .map(|name| name.to_lowercase())
+                .collect()),
+        }
+    }
+}
+
+/// Runs a blocking libc lookup off the runtime; the outer error is a timeout.
+async fn blocking<T: Send + 'static>(
+    timeout: Duration,
+    lookup: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> Result<std::io::Result<T>, String> {
+    match tokio::time::timeout(timeout, tokio::task::spawn_blocking(lookup)).await {
+        Ok(Ok(result)) => Ok(result),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err(format!("timed out after {:?}", timeout)),
+    }
+}
+
+/// `address name [aliases...]` lines; the first name is the canonical one,
+/// which is what a PTR lookup returns.
+async fn read_hosts(path: &PathBuf) -> Result<Vec<(IpAddr, Vec<String>)>, String> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let address = fields.next()?.parse::<IpAddr>().ok()?;
+            let names: Vec<String> = fields
+                .map(|name| name.trim_end_matches('.').to_string())
+                .collect();
+            (!names.is_empty()).then_some((address, names))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use ipnetwork::IpNetwork;
+
+    use super::*;
+    use crate::domains::models::server_dns::{DnsCheckModel, DnsIssue};
+
+    const HOSTS: &str = "\
+# generated for the tests
+127.0.0.1   localhost
+10.0.0.5    web1.example.com. web1 www.example.com  # primary
+10.0.0.6    db1.example.com db1
+10.0.0.6    replica.example.com
+fe80::1     v6.example.com
+
+not-an-address  ignored.example.com
+10.0.0.7
+# 10.0.0.8  commented.example.com
+";
+
+    /// A hosts file of its own for every test, removed again on drop.
+    struct HostsFile(PathBuf);
+
+    impl HostsFile {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "inventory-dns-{}-{}.hosts",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, HOSTS).unwrap();
+            HostsFile(path)
+        }
+
+        fn resolver(&self) -> Resolver {
+            Resolver::HostsFile(self.0.clone())
+        }
+    }
+
+    impl Drop for HostsFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_addresses_names_and_aliases() {
+        let hosts = HostsFile::new("read");
+        let entries = read_hosts(&hosts.0).await.unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                (ip("127.0.0.1"), vec![String::from("localhost")]),
+                (
+                    ip("10.0.0.5"),
+                    vec![
+                        String::from("web1.example.com"),
+                        String::from("web1"),
+                        String::from("www.example.com"),
+                    ]
+                ),
+                (
+                    ip("10.0.0.6"),
+                    vec![String::from("db1.example.com"), String::from("db1")]
+                ),
+                (ip("10.0.0.6"), vec![String::from("replica.example.com")]),
+                (ip("fe80::1"), vec![String::from("v6.example.com")]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_hosts_file_is_an_error() {
+        let resolver = Resolver::HostsFile(PathBuf::from("/nonexistent/inventory.hosts"));
+        assert!(resolver.lookup_host("web1").await.is_err());
+        assert!(resolver.lookup_addr(ip("10.0.0.5")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn looks_hosts_up_by_any_name() {
+        let hosts = HostsFile::new("host");
+        let resolver = hosts.resolver();
+
+        for name in ["web1.example.com", "WEB1", "www.example.com"] {
+            assert_eq!(
+                resolver.lookup_host(name).await,
+                Ok(vec![ip("10.0.0.5")]),
+                "{}",
+                name
+            );
+        }
+        assert_eq!(
+            resolver.lookup_host("v6.example.com").await,
+            Ok(vec![ip("fe80::1")])
+        );
+        assert!(resolver.lookup_host("commented.example.com").await.is_err());
+        assert!(resolver.lookup_host("ignored.example.com").await.is_err());
+    }
+
+    /// What the DNS check would record for a server named `hostname` at `address`.
+    async fn check(resolver: &Resolver, hostname: &str, address: &str) -> DnsCheckModel {
+        let forward = resolver.lookup_host(hostname).await;
+        let reverse = resolver.lookup_addr(ip(address)).await;
+        DnsCheckModel {
+            hostname: Some(hostname.to_string()),
+            ip: IpNetwork::from(ip(address)),
+            addresses: forward.clone().unwrap_or_default(),
+            forward_error: forward.err(),
+            ptr_names: reverse.clone().unwrap_or_default(),
+            reverse_error: reverse.err(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn detects_mismatches_against_the_recorded_address() {
+        let hosts = HostsFile::new("mismatch");
+        let resolver = hosts.resolver();
+
+        assert_eq!(
+            check(&resolver, "web1.example.com", "10.0.0.5")
+                .await
+                .issues(),
+            vec![]
+        );
+        assert_eq!(
+            check(&resolver, "replica.example.com", "10.0.0.6")
+                .await
+                .issues(),
+            vec![]
+        );
+        assert_eq!(
+            check(&resolver, "web1.example.com", "10.0.0.6")
+                .await
+                .issues(),
+            vec![DnsIssue::AddressMismatch, DnsIssue::PtrMismatch]
+        );
+        // An address without PTR records has nothing to disagree with.
+        assert_eq!(
+            check(&resolver, "web1.example.com", "10.0.0.9")
+                .await
+                .issues(),
+            vec![DnsIssue::AddressMismatch]
+        );
+
+        let unresolvable = check(&resolver, "nowhere.example.com", "10.0.0.5").await;
+        assert_eq!(
+            unresolvable.forward_error.as_deref(),
+            Some("`nowhere.example.com` does not resolve")
+        );
+        assert_eq!(
+            unresolvable.issues(),
+            vec![DnsIssue::Unresolvable, DnsIssue::PtrMismatch]
+        );
+    }
+
+    #[tokio::test]
+    async fn reverse_lookup_returns_canonical_names() {
+        let hosts = HostsFile::new("addr");
+        let resolver = hosts.resolver();
+
+        assert_eq!(
+            resolver.lookup_addr(ip("10.0.0.5")).await,
+            Ok(vec![String::from("web1.example.com")])
+        );
+        assert_eq!(
+            resolver.lookup_addr(ip("10.0.0.6")).await,
+            Ok(vec![
+                String::from("db1.example.com"),
+                String::from("replica.example.com"),
+            ])
+        );
+        assert_eq!(resolver.lookup_addr(ip("10.0.0.9")).await, Ok(Vec::new()));
+    }
+}
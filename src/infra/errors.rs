@@ -1,10 +1,23 @@
 use std::fmt;
 use deadpool_diesel::InteractError;
+use diesel::result::DatabaseErrorKind;
 
 #[derive(Debug)]
 pub enum InfraError {
     InternalServerError,
     NotFound,
+    /// The row changed since it was read, its version no longer matches.
+    Conflict,
+    /// A unique constraint of the database rejected the row, the constraint is named.
+    UniqueViolation(String),
+    /// The change breaks a rule kept in the database, the message says which.
+    Invalid(String),
+    /// The change would give a value that has to be unique to a second row,
+    /// the message says which.
+    Duplicate(String),
+    /// The row is still referenced and cannot go without an explicit override,
+    /// the message says by what.
+    InUse(String),
 }
 
 pub fn adapt_infra_error<T: Error>(error: T) -> InfraError {
@@ -16,6 +29,13 @@ impl fmt::Display for InfraError {
         match self {
             InfraError::NotFound => write!(f, "Not found"),
             InfraError::InternalServerError => write!(f, "Internal server error"),
+            InfraError::Conflict => write!(f, "Conflict"),
+            InfraError::UniqueViolation(constraint) => {
+                write!(f, "Violates the unique constraint `{}`", constraint)
+            }
+            InfraError::Invalid(message) => write!(f, "{}", message),
+            InfraError::Duplicate(message) => write!(f, "{}", message),
+            InfraError::InUse(message) => write!(f, "{}", message),
         }
     }
 }
@@ -28,11 +48,20 @@ impl Error for diesel::result::Error {
     fn as_infra_error(&self) -> InfraError {
         match self {
             diesel::result::Error::NotFound => InfraError::NotFound,
+            diesel::result::Error::DatabaseError(DatabaseErrorKind::UniqueViolation, info) => {
+                InfraError::UniqueViolation(info.constraint_name().unwrap_or_default().to_string())
+            }
             _ => InfraError::InternalServerError,
         }
     }
 }
 
+impl From<diesel::result::Error> for InfraError {
+    fn from(error: diesel::result::Error) -> Self {
+        error.as_infra_error()
+    }
+}
+
 impl Error for deadpool_diesel::PoolError {
     fn as_infra_error(&self) -> InfraError {
         InfraError::InternalServerError
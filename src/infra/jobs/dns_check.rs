@@ -0,0 +1,97 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use chrono::Utc;
+use deadpool_diesel::postgres::Pool;
+use futures::{stream, StreamExt};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::server_dns::DnsCheckModel;
+use crate::infra::dns::Resolver;
+use crate::infra::errors::InfraError;
+use crate::infra::respository::server_dns::{self, DnsTargetDB, ServerDnsCheckDB};
+
+/// Servers looked up at the same time.
+const CONCURRENCY: usize = 16;
+
+/// Periodically resolves the hostname of every live server and the PTR records
+/// of its address. Servers registered with a subnet are skipped.
+pub async fn run(pool: Pool, resolver: Resolver, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        match check(&pool, &resolver, None).await {
+            Ok(checked) => tracing::debug!("dns checked {} servers", checked),
+            Err(err) => tracing::error!("dns checking servers failed: {}", err),
+        }
+    }
+}
+
+/// Looks `server_ids` up in the background, for servers that were just created
+/// or got a new address or hostname.
+pub fn spawn_check(pool: Pool, resolver: Resolver, server_ids: Vec<Uuid>) {
+    if server_ids.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        if let Err(err) = check(&pool, &resolver, Some(server_ids)).await {
+            tracing::error!("dns checking changed servers failed: {}", err);
+        }
+    });
+}
+
+async fn check(
+    pool: &Pool,
+    resolver: &Resolver,
+    server_ids: Option<Vec<Uuid>>,
+) -> Result<usize, InfraError> {
+    let targets: Vec<DnsTargetDB> = server_dns::get_targets(pool, server_ids)
+        .await?
+        .into_iter()
+        .filter(|target| target.ip.prefix() == IpNetwork::from(target.ip.ip()).prefix())
+        .collect();
+
+    let checks: Vec<ServerDnsCheckDB> = stream::iter(targets)
+        .map(|target| check_server(resolver, target))
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+
+    server_dns::save(pool, checks).await
+}
+
+async fn check_server(resolver: &Resolver, target: DnsTargetDB) -> ServerDnsCheckDB {
+    let address: IpAddr = target.ip.ip();
+    let forward = match &target.hostname {
+        Some(hostname) => Some(resolver.lookup_host(hostname).await),
+        None => None,
+    };
+    let reverse = resolver.lookup_addr(address).await;
+
+    let (addresses, forward_error) = match forward {
+        Some(Ok(addresses)) => (addresses, None),
+        Some(Err(err)) => (Vec::new(), Some(err)),
+        None => (Vec::new(), None),
+    };
+    let (ptr_names, reverse_error) = match reverse {
+        Ok(names) => (names, None),
+        Err(err) => (Vec::new(), Some(err)),
+    };
+
+    ServerDnsCheckDB::new(
+        target.server_id,
+        DnsCheckModel {
+            hostname: target.hostname,
+            ip: target.ip,
+            addresses,
+            forward_error,
+            ptr_names,
+            reverse_error,
+            checked_at: Utc::now(),
+        },
+    )
+}
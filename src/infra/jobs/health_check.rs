@@ -0,0 +1,180 @@
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use deadpool_diesel::postgres::Pool;
+use futures::{stream, StreamExt};
+use ipnetwork::IpNetwork;
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+use crate::domains::models::server_check::{PortCheck, ServerCheckModel};
+use crate::infra::errors::InfraError;
+use crate::infra::respository::server_checks::{self, NewServerCheckDB};
+use crate::infra::respository::uptime;
+
+#[derive(Debug, Clone)]
+pub struct HealthCheckSettings {
+    pub every: Duration,
+    /// Connect timeout for a single port.
+    pub timeout: Duration,
+    /// Servers checked at the same time; the ports of one server are checked together.
+    pub concurrency: usize,
+    /// Ports for servers without their own `check_ports`.
+    pub default_ports: Vec<u16>,
+    /// Age after which samples are only kept as hourly rollups.
+    pub raw_retention: chrono::Duration,
+    /// Age after which rollups and closed outages are dropped.
+    pub rollup_retention: chrono::Duration,
+}
+
+/// Periodically TCP-connects to every live server and stores the outcome, then
+/// rolls the uptime history up. Servers registered with a subnet rather than a
+/// single address are skipped.
+pub async fn run(pool: Pool, settings: HealthCheckSettings) {
+    let mut interval = tokio::time::interval(settings.every);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        match check_all(&pool, &settings).await {
+            Ok(checked) => tracing::debug!("health checked {} servers", checked),
+            Err(err) => tracing::error!("health checking servers failed: {}", err),
+        }
+
+        if let Err(err) =
+            uptime::roll_up(&pool, settings.raw_retention, settings.rollup_retention).await
+        {
+            tracing::error!("rolling up uptime history failed: {}", err);
+        }
+    }
+}
+
+async fn check_all(pool: &Pool, settings: &HealthCheckSettings) -> Result<usize, InfraError> {
+    let targets = server_checks::get_targets(pool).await?;
+
+    let timeout = settings.timeout;
+    let probes: Vec<(Uuid, IpAddr, Vec<u16>)> = targets
+        .into_iter()
+        .filter(|target| target.ip.prefix() == IpNetwork::from(target.ip.ip()).prefix())
+        .map(|target| {
+            let mut ports: Vec<u16> = target
+                .check_ports
+                .iter()
+                .filter_map(|port| u16::try_from(*port).ok())
+                .collect();
+            if ports.is_empty() {
+                ports = settings.default_ports.clone();
+            }
+            (target.server_id, target.ip.ip(), ports)
+        })
+        .collect();
+
+    let checks: Vec<NewServerCheckDB> = stream::iter(probes)
+        .map(|(server_id, address, ports)| check_server(server_id, address, ports, timeout))
+        .buffer_unordered(settings.concurrency.max(1))
+        .collect()
+        .await;
+
+    server_checks::save(pool, checks).await
+}
+
+async fn check_server(
+    server_id: Uuid,
+    address: IpAddr,
+    ports: Vec<u16>,
+    timeout: Duration,
+) -> NewServerCheckDB {
+    let ports = futures::future::join_all(
+        ports
+            .into_iter()
+            .map(|port| check_port(SocketAddr::new(address, port), timeout)),
+    )
+    .await;
+
+    NewServerCheckDB::new(server_id, &ServerCheckModel::from_ports(ports, Utc::now()))
+}
+
+pub async fn check_port(address: SocketAddr, timeout: Duration) -> PortCheck {
+    let started = Instant::now();
+    let (ok, error) = match tokio::time::timeout(timeout, TcpStream::connect(address)).await {
+        Ok(Ok(_)) => (true, None),
+        Ok(Err(err)) => (false, Some(err.to_string())),
+        Err(_) => (false, Some(format!("timed out after {:?}", timeout))),
+    };
+
+    PortCheck {
+        port: address.port(),
+        ok,
+        latency_ms: ok.then(|| started.elapsed().as_millis().min(i32::MAX as u128) as i32),
+        error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::net::{TcpListener, TcpSocket};
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(500);
+
+    fn localhost(port: u16) -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port)
+    }
+
+    #[tokio::test]
+    async fn open_port_is_up() {
+        let listener = TcpListener::bind(localhost(0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let check = check_port(address, TIMEOUT).await;
+        assert_eq!(check.port, address.port());
+        assert!(check.ok);
+        assert!(check.latency_ms.is_some());
+        assert_eq!(check.error, None);
+    }
+
+    #[tokio::test]
+    async fn closed_port_is_down() {
+        let address = TcpListener::bind(localhost(0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let check = check_port(address, TIMEOUT).await;
+        assert!(!check.ok);
+        assert_eq!(check.latency_ms, None);
+        assert!(check.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn unanswered_port_times_out() {
+        // A listener that never accepts stops answering handshakes once its
+        // backlog is full, so later connections hang until the timeout.
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.bind(localhost(0)).unwrap();
+        let address = socket.local_addr().unwrap();
+        let _listener = socket.listen(0).unwrap();
+
+        let mut held = Vec::new();
+        let mut timed_out = None;
+        for _ in 0..8 {
+            match tokio::time::timeout(TIMEOUT, TcpStream::connect(address)).await {
+                Ok(Ok(stream)) => held.push(stream),
+                _ => {
+                    timed_out = Some(check_port(address, Duration::from_millis(50)).await);
+                    break;
+                }
+            }
+        }
+
+        let check = timed_out.expect("the backlog never filled up");
+        assert!(!check.ok);
+        assert_eq!(check.error.as_deref(), Some("timed out after 50ms"));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod dns_check;
+pub mod health_check;
+pub mod trash_purge;
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use deadpool_diesel::postgres::Pool;
+
+use crate::infra::respository;
+
+/// Periodically hard-deletes servers that have been in the trash longer than `retention`.
+pub async fn run(pool: Pool, retention: chrono::Duration, every: Duration) {
+    let mut interval = tokio::time::interval(every);
+
+    loop {
+        interval.tick().await;
+
+        let deleted_before = Utc::now() - retention;
+        match respository::servers::purge_deleted(&pool, deleted_before).await {
+            Ok(0) => {}
+            Ok(purged) => tracing::info!("purged {} servers from the trash", purged),
+            Err(err) => tracing::error!("purging the trash failed: {}", err),
+        }
+    }
+}
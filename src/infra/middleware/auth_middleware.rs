@@ -1,13 +1,22 @@
+use std::sync::Arc;
+
 use axum::{
-    extract::Request,
+    extract::{Request, State},
     http::{self, StatusCode},
     middleware::Next,
     response::Response,
 };
 use axum_extra::extract::CookieJar;
 use jsonwebtoken::{decode, errors::ErrorKind, DecodingKey, Validation};
+use uuid::Uuid;
 
-use crate::{handlers::user::Claims, infra::respository::user::JWT_SECRET, AppState};
+use crate::{
+    config::config,
+    domains::models::api_key::{hash_api_key, API_KEY_PREFIX},
+    handlers::user::Claims,
+    infra::respository::{self, user::JWT_SECRET},
+    AppState,
+};
 
 pub async fn jwt_token_check(mut req: Request, next: Next) -> Result<Response, StatusCode> {
     tracing::info!("Checking JWT token: {:?}", &req);
@@ -17,13 +26,22 @@ pub async fn jwt_token_check(mut req: Request, next: Next) -> Result<Response, S
     let session_cookie = cookie_jar.get("session");
     tracing::info!("session cookie: {:?}", session_cookie);
 
+    // Scripts without a cookie jar can send the same token as a bearer token.
+    let bearer_token = req
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
     let jwt_token = if let Some(cookie) = session_cookie {
-        cookie.value()
+        cookie.value().to_string()
+    } else if let Some(token) = bearer_token {
+        token.trim().to_string()
     } else {
         return Err(StatusCode::UNAUTHORIZED);
     };
 
-    if let Some(current_user) = authorize_current_user(jwt_token).await {
+    if let Some(current_user) = authorize_current_user(&jwt_token).await {
         req.extensions_mut().insert(current_user);
         Ok(next.run(req).await)
     } else {
@@ -31,17 +49,65 @@ pub async fn jwt_token_check(mut req: Request, next: Next) -> Result<Response, S
     }
 }
 
+/// Authenticates machine clients such as Prometheus that send an API key as
+/// `Authorization: Bearer inv_...` and cannot go through the cookie sign in.
+pub async fn api_key_check(
+    State(state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let api_key = req
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|key| key.starts_with(API_KEY_PREFIX))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let user_id = respository::api_keys::authenticate(&state.pool, hash_api_key(api_key))
+        .await
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    req.extensions_mut().insert(CurrentUser { user_id });
+    Ok(next.run(req).await)
+}
+
+/// Only lets users listed in `ADMIN_USERNAMES` through; layered inside
+/// `jwt_token_check`, which provides the `CurrentUser`.
+pub async fn admin_check(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let user_id = req
+        .extensions()
+        .get::<CurrentUser>()
+        .map(|current_user| current_user.user_id)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let username = respository::user::get_username(&state.pool, user_id)
+        .await
+        .map_err(|_| StatusCode::FORBIDDEN)?;
+
+    if config().await.admin_usernames().contains(&username) {
+        Ok(next.run(req).await)
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CurrentUser {
-    pub user_id: String,
+    pub user_id: Uuid,
 }
 
 async fn authorize_current_user(auth_token: &str) -> Option<CurrentUser> {
     let decoding_key = DecodingKey::from_secret(JWT_SECRET.as_ref());
     match decode::<Claims>(&auth_token, &decoding_key, &Validation::default()) {
-        Ok(token_data) => Some(CurrentUser {
-            user_id: token_data.claims.sub.to_string(),
-        }),
+        Ok(token_data) => Uuid::parse_str(&token_data.claims.sub)
+            .ok()
+            .map(|user_id| CurrentUser { user_id }),
         Err(err) => {
             match *err.kind() {
                 ErrorKind::InvalidToken => println!("Token is invalid"),
@@ -1,3 +1,6 @@
 pub mod respository;
+pub mod dns;
 pub mod errors;
-pub mod middleware;
\ No newline at end of file
+pub mod middleware;
+pub mod jobs;
+pub mod vault;
\ No newline at end of file
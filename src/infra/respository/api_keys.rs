@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::api_key::ApiKeyModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::schema::user_tokens;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = user_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiKeyDB {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = user_tokens)]
+pub struct NewApiKeyDB {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token: String,
+    pub name: String,
+}
+
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_api_key: NewApiKeyDB,
+) -> Result<ApiKeyModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(user_tokens::table)
+                .values(new_api_key)
+                .returning(ApiKeyDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_api_key_db_to_api_key(res))
+}
+
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+) -> Result<Vec<ApiKeyModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            user_tokens::table
+                .filter(user_tokens::user_id.eq(user_id))
+                .order((user_tokens::created_at.asc(), user_tokens::id.asc()))
+                .select(ApiKeyDB::as_select())
+                .load::<ApiKeyDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res.into_iter().map(adapt_api_key_db_to_api_key).collect())
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+    id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            diesel::delete(
+                user_tokens::table
+                    .filter(user_tokens::id.eq(id))
+                    .filter(user_tokens::user_id.eq(user_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+/// The owner of the key with this hash, recording that the key was used.
+pub async fn authenticate(
+    pool: &deadpool_diesel::postgres::Pool,
+    token_hash: String,
+) -> Result<Uuid, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        diesel::update(user_tokens::table.filter(user_tokens::token.eq(token_hash)))
+            .set(user_tokens::last_used_at.eq(diesel::dsl::now))
+            .returning(user_tokens::user_id)
+            .get_result(conn)
+    })
+    .await
+    .map_err(adapt_infra_error)?
+    .map_err(adapt_infra_error)
+}
+
+fn adapt_api_key_db_to_api_key(api_key_db: ApiKeyDB) -> ApiKeyModel {
+    ApiKeyModel {
+        id: api_key_db.id,
+        name: api_key_db.name,
+        created_at: api_key_db.created_at,
+        last_used_at: api_key_db.last_used_at,
+    }
+}
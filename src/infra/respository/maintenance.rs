@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::maintenance::MaintenanceWindowModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::schema::{server_maintenance_windows, servers};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_maintenance_windows)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MaintenanceWindowDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub rrule: Option<String>,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_maintenance_windows)]
+pub struct NewMaintenanceWindowDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub rrule: Option<String>,
+    pub reason: Option<String>,
+    pub created_by: Uuid,
+}
+
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    new_window: NewMaintenanceWindowDB,
+) -> Result<MaintenanceWindowModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, new_window.server_id)?;
+
+            diesel::insert_into(server_maintenance_windows::table)
+                .values(new_window)
+                .returning(MaintenanceWindowDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    adapt_window_db_to_window(res)
+}
+
+/// The windows of one of the owner's servers, by first start.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<MaintenanceWindowModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_maintenance_windows::table
+                .filter(server_maintenance_windows::server_id.eq(server_id))
+                .order((
+                    server_maintenance_windows::starts_at.asc(),
+                    server_maintenance_windows::id.asc(),
+                ))
+                .select(MaintenanceWindowDB::as_select())
+                .load::<MaintenanceWindowDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    res.into_iter().map(adapt_window_db_to_window).collect()
+}
+
+/// Windows of already authorized servers that may be in effect at `at`,
+/// grouped by server.
+pub async fn get_for_servers(
+    pool: &deadpool_diesel::postgres::Pool,
+    server_ids: Vec<Uuid>,
+    at: DateTime<Utc>,
+) -> Result<HashMap<Uuid, Vec<MaintenanceWindowModel>>, InfraError> {
+    if server_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            server_maintenance_windows::table
+                .filter(server_maintenance_windows::server_id.eq_any(server_ids))
+                .filter(server_maintenance_windows::starts_at.le(at))
+                .filter(
+                    server_maintenance_windows::rrule
+                        .is_not_null()
+                        .or(server_maintenance_windows::ends_at.gt(at)),
+                )
+                .select(MaintenanceWindowDB::as_select())
+                .load::<MaintenanceWindowDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    let mut windows: HashMap<Uuid, Vec<MaintenanceWindowModel>> = HashMap::new();
+    for window_db in res {
+        windows
+            .entry(window_db.server_id)
+            .or_default()
+            .push(adapt_window_db_to_window(window_db)?);
+    }
+    Ok(windows)
+}
+
+/// Windows of the owner's live servers that may be in effect at `at`, with
+/// the name and address of their server.
+pub async fn get_candidates(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    at: DateTime<Utc>,
+) -> Result<Vec<(String, IpNetwork, MaintenanceWindowModel)>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            server_maintenance_windows::table
+                .inner_join(servers::table)
+                .filter(servers::owner_id.eq(owner_id))
+                .filter(servers::deleted_at.is_null())
+                .filter(server_maintenance_windows::starts_at.le(at))
+                .filter(
+                    server_maintenance_windows::rrule
+                        .is_not_null()
+                        .or(server_maintenance_windows::ends_at.gt(at)),
+                )
+                .order((servers::name.asc(), server_maintenance_windows::id.asc()))
+                .select((servers::name, servers::ip, MaintenanceWindowDB::as_select()))
+                .load::<(String, IpNetwork, MaintenanceWindowDB)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    res.into_iter()
+        .map(|(name, ip, window_db)| Ok((name, ip, adapt_window_db_to_window(window_db)?)))
+        .collect()
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::delete(
+                server_maintenance_windows::table
+                    .filter(server_maintenance_windows::id.eq(id))
+                    .filter(server_maintenance_windows::server_id.eq(server_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+/// A stored rule that no longer parses is an error rather than a one-off
+/// window, which would quietly stop covering the later occurrences.
+fn adapt_window_db_to_window(
+    window_db: MaintenanceWindowDB,
+) -> Result<MaintenanceWindowModel, InfraError> {
+    let recurrence = window_db
+        .rrule
+        .map(|rrule| rrule.parse())
+        .transpose()
+        .map_err(|error| {
+            tracing::error!(
+                "Maintenance window {} has an unreadable recurrence: {}",
+                window_db.id,
+                error
+            );
+            InfraError::InternalServerError
+        })?;
+    Ok(MaintenanceWindowModel {
+        id: window_db.id,
+        server_id: window_db.server_id,
+        starts_at: window_db.starts_at,
+        ends_at: window_db.ends_at,
+        recurrence,
+        reason: window_db.reason,
+        created_at: window_db.created_at,
+    })
+}
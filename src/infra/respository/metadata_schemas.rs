@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::metadata::MetadataSchemaModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::schema::metadata_schemas;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = metadata_schemas)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MetadataSchemaDB {
+    pub id: Uuid,
+    pub name: String,
+    pub label_selector: String,
+    pub schema: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = metadata_schemas)]
+pub struct NewMetadataSchemaDB {
+    pub id: Uuid,
+    pub name: String,
+    pub label_selector: String,
+    pub schema: serde_json::Value,
+    pub created_by: Uuid,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = metadata_schemas)]
+pub struct UpdateMetadataSchemaDB {
+    pub name: Option<String>,
+    pub label_selector: Option<String>,
+    pub schema: Option<serde_json::Value>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Every schema, for validating a server change inside its transaction.
+pub fn load_all(conn: &mut PgConnection) -> Result<Vec<MetadataSchemaModel>, InfraError> {
+    let schemas = metadata_schemas::table
+        .order(metadata_schemas::name.asc())
+        .select(MetadataSchemaDB::as_select())
+        .load::<MetadataSchemaDB>(conn)?;
+
+    schemas
+        .into_iter()
+        .map(adapt_metadata_schema_db_to_metadata_schema)
+        .collect()
+}
+
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_schema: NewMetadataSchemaDB,
+) -> Result<MetadataSchemaModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            diesel::insert_into(metadata_schemas::table)
+                .values(new_schema)
+                .returning(MetadataSchemaDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    adapt_metadata_schema_db_to_metadata_schema(res)
+}
+
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+) -> Result<Vec<MetadataSchemaModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn.interact(load_all).await.map_err(adapt_infra_error)??;
+
+    Ok(res)
+}
+
+pub async fn update(
+    pool: &deadpool_diesel::postgres::Pool,
+    id: Uuid,
+    changes: UpdateMetadataSchemaDB,
+) -> Result<MetadataSchemaModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::update(metadata_schemas::table.filter(metadata_schemas::id.eq(id)))
+                .set(&changes)
+                .returning(MetadataSchemaDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    adapt_metadata_schema_db_to_metadata_schema(res)
+}
+
+pub async fn delete(pool: &deadpool_diesel::postgres::Pool, id: Uuid) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            diesel::delete(metadata_schemas::table.filter(metadata_schemas::id.eq(id)))
+                .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+/// A stored selector that no longer parses is an error rather than a skipped
+/// schema, which would let servers through unvalidated.
+fn adapt_metadata_schema_db_to_metadata_schema(
+    schema_db: MetadataSchemaDB,
+) -> Result<MetadataSchemaModel, InfraError> {
+    let label_selector = schema_db.label_selector.parse().map_err(|error| {
+        tracing::error!(
+            "Metadata schema {} has an unreadable label selector: {}",
+            schema_db.id,
+            error
+        );
+        InfraError::InternalServerError
+    })?;
+    Ok(MetadataSchemaModel {
+        id: schema_db.id,
+        name: schema_db.name,
+        label_selector,
+        label_selector_text: schema_db.label_selector,
+        schema: schema_db.schema,
+        created_at: schema_db.created_at,
+        updated_at: schema_db.updated_at,
+    })
+}
@@ -1,2 +1,16 @@
+pub mod api_keys;
+pub mod maintenance;
+pub mod metadata_schemas;
+pub mod saved_views;
+pub mod server_checks;
+pub mod server_comments;
+pub mod server_dependencies;
+pub mod server_dns;
+pub mod server_history;
+pub mod server_pins;
+pub mod server_secrets;
 pub mod servers;
+pub mod services;
+pub mod subnets;
+pub mod uptime;
 pub mod user;
\ No newline at end of file
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::saved_view::SavedViewModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::schema::saved_views;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = saved_views)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SavedViewDB {
+    pub id: Uuid,
+    pub name: String,
+    pub filter: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = saved_views)]
+pub struct NewSavedViewDB {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub filter: serde_json::Value,
+}
+
+/// Fails with `InfraError::UniqueViolation` when the owner has a view of that name.
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_view: NewSavedViewDB,
+) -> Result<SavedViewModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            diesel::insert_into(saved_views::table)
+                .values(new_view)
+                .returning(SavedViewDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    adapt_saved_view_db_to_saved_view(res)
+}
+
+/// The owner's views by name.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+) -> Result<Vec<SavedViewModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            saved_views::table
+                .filter(saved_views::owner_id.eq(owner_id))
+                .order(saved_views::name.asc())
+                .select(SavedViewDB::as_select())
+                .load::<SavedViewDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    res.into_iter()
+        .map(adapt_saved_view_db_to_saved_view)
+        .collect()
+}
+
+pub async fn get(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+) -> Result<SavedViewModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            saved_views::table
+                .filter(saved_views::id.eq(id))
+                .filter(saved_views::owner_id.eq(owner_id))
+                .select(SavedViewDB::as_select())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    adapt_saved_view_db_to_saved_view(res)
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            diesel::delete(
+                saved_views::table
+                    .filter(saved_views::id.eq(id))
+                    .filter(saved_views::owner_id.eq(owner_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+/// A stored filter that is not a map of strings is an error rather than no
+/// filter, which would list every server.
+fn adapt_saved_view_db_to_saved_view(
+    saved_view_db: SavedViewDB,
+) -> Result<SavedViewModel, InfraError> {
+    let filter = serde_json::from_value(saved_view_db.filter).map_err(|error| {
+        tracing::error!(
+            "Saved view {} has an unreadable filter: {}",
+            saved_view_db.id,
+            error
+        );
+        InfraError::InternalServerError
+    })?;
+    Ok(SavedViewModel {
+        id: saved_view_db.id,
+        name: saved_view_db.name,
+        filter,
+        created_at: saved_view_db.created_at,
+    })
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{deserialize::Queryable, Selectable};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::server_check::{ServerCheckModel, ServerStatus};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::uptime;
+use crate::schema::{server_checks, servers};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_checks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerCheckDB {
+    pub server_id: Uuid,
+    pub status: String,
+    pub latency_ms: Option<i32>,
+    pub ports: serde_json::Value,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_checks)]
+pub struct NewServerCheckDB {
+    pub server_id: Uuid,
+    pub status: String,
+    pub latency_ms: Option<i32>,
+    pub ports: serde_json::Value,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl NewServerCheckDB {
+    pub fn new(server_id: Uuid, check: &ServerCheckModel) -> Self {
+        NewServerCheckDB {
+            server_id,
+            status: check.status.as_str().to_string(),
+            latency_ms: check.latency_ms,
+            ports: serde_json::to_value(&check.ports).unwrap_or_default(),
+            checked_at: check.checked_at,
+        }
+    }
+}
+
+/// A live server the health checker should probe.
+#[derive(Queryable)]
+pub struct CheckTargetDB {
+    pub server_id: Uuid,
+    pub ip: IpNetwork,
+    pub check_ports: Vec<i32>,
+}
+
+/// Every live server of every owner.
+pub async fn get_targets(
+    pool: &deadpool_diesel::postgres::Pool,
+) -> Result<Vec<CheckTargetDB>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            servers::table
+                .filter(servers::deleted_at.is_null())
+                .select((servers::id, servers::ip, servers::check_ports))
+                .load::<CheckTargetDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+/// Replaces the latest check of each server and records it in the uptime
+/// history. Servers purged while they were being checked are skipped.
+pub async fn save(
+    pool: &deadpool_diesel::postgres::Pool,
+    checks: Vec<NewServerCheckDB>,
+) -> Result<usize, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            let server_ids: Vec<Uuid> = checks.iter().map(|check| check.server_id).collect();
+            let existing: Vec<Uuid> = servers::table
+                .filter(servers::id.eq_any(server_ids))
+                .select(servers::id)
+                .load(conn)?;
+            let checks: Vec<NewServerCheckDB> = checks
+                .into_iter()
+                .filter(|check| existing.contains(&check.server_id))
+                .collect();
+
+            conn.transaction(|conn| {
+                uptime::record_samples(conn, &checks)?;
+
+                diesel::insert_into(server_checks::table)
+                    .values(checks)
+                    .on_conflict(server_checks::server_id)
+                    .do_update()
+                    .set((
+                        server_checks::status.eq(excluded(server_checks::status)),
+                        server_checks::latency_ms.eq(excluded(server_checks::latency_ms)),
+                        server_checks::ports.eq(excluded(server_checks::ports)),
+                        server_checks::checked_at.eq(excluded(server_checks::checked_at)),
+                    ))
+                    .execute(conn)
+            })
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+pub async fn get_latest(
+    pool: &deadpool_diesel::postgres::Pool,
+    server_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, ServerCheckModel>, InfraError> {
+    if server_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            server_checks::table
+                .filter(server_checks::server_id.eq_any(server_ids))
+                .select(ServerCheckDB::as_select())
+                .load::<ServerCheckDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res
+        .into_iter()
+        .filter_map(|check_db| {
+            let server_id = check_db.server_id;
+            adapt_server_check_db_to_server_check(check_db).map(|check| (server_id, check))
+        })
+        .collect())
+}
+
+fn adapt_server_check_db_to_server_check(check_db: ServerCheckDB) -> Option<ServerCheckModel> {
+    Some(ServerCheckModel {
+        status: ServerStatus::parse(&check_db.status)?,
+        latency_ms: check_db.latency_ms,
+        ports: serde_json::from_value(check_db.ports).unwrap_or_default(),
+        checked_at: check_db.checked_at,
+    })
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, dsl, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::comment::CommentModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::schema::{server_comments, users};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_comments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct CommentDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_comments)]
+pub struct NewCommentDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub author_id: Uuid,
+    pub body: String,
+}
+
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    new_comment: NewCommentDB,
+) -> Result<CommentModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, new_comment.server_id)?;
+
+            let (server_id, id) = (new_comment.server_id, new_comment.id);
+            diesel::insert_into(server_comments::table)
+                .values(new_comment)
+                .execute(conn)?;
+            load_one(conn, server_id, id)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_comment_db_to_comment(res))
+}
+
+/// The thread of one of the owner's servers, oldest comment first.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<CommentModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_comments::table
+                .left_join(users::table)
+                .filter(server_comments::server_id.eq(server_id))
+                .order((server_comments::created_at.asc(), server_comments::id.asc()))
+                .select((CommentDB::as_select(), users::username.nullable()))
+                .load::<(CommentDB, Option<String>)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res.into_iter().map(adapt_comment_db_to_comment).collect())
+}
+
+pub async fn get(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+) -> Result<CommentModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            load_one(conn, server_id, id)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_comment_db_to_comment(res))
+}
+
+/// Replaces the body of a comment written by `author_id` and marks it edited.
+pub async fn update(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+    author_id: Uuid,
+    body: String,
+) -> Result<CommentModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::update(
+                server_comments::table
+                    .filter(server_comments::id.eq(id))
+                    .filter(server_comments::server_id.eq(server_id))
+                    .filter(server_comments::author_id.eq(author_id)),
+            )
+            .set((
+                server_comments::body.eq(body),
+                server_comments::edited_at.eq(dsl::now),
+            ))
+            .returning(server_comments::id)
+            .get_result::<Uuid>(conn)?;
+            load_one(conn, server_id, id)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_comment_db_to_comment(res))
+}
+
+/// Removes a comment written by `author_id`.
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+    author_id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::delete(
+                server_comments::table
+                    .filter(server_comments::id.eq(id))
+                    .filter(server_comments::server_id.eq(server_id))
+                    .filter(server_comments::author_id.eq(author_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+fn load_one(
+    conn: &mut PgConnection,
+    server_id: Uuid,
+    id: Uuid,
+) -> QueryResult<(CommentDB, Option<String>)> {
+    server_comments::table
+        .left_join(users::table)
+        .filter(server_comments::id.eq(id))
+        .filter(server_comments::server_id.eq(server_id))
+        .select((CommentDB::as_select(), users::username.nullable()))
+        .get_result(conn)
+}
+
+fn adapt_comment_db_to_comment((comment_db, author): (CommentDB, Option<String>)) -> CommentModel {
+    CommentModel {
+        id: comment_db.id,
+        server_id: comment_db.server_id,
+        author_id: comment_db.author_id,
+        author,
+        body: comment_db.body,
+        created_at: comment_db.created_at,
+        edited_at: comment_db.edited_at,
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, HashSet};
+
+use diesel::prelude::*;
+use diesel::sql_types;
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::server_dependency::{DependencyModel, DependentModel};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::schema::{server_dependencies, servers};
+
+#[derive(Insertable)]
+#[diesel(table_name = server_dependencies)]
+pub struct NewServerDependencyDB {
+    pub server_id: Uuid,
+    pub depends_on_id: Uuid,
+    pub created_by: Uuid,
+}
+
+#[derive(QueryableByName)]
+struct Reachable {
+    #[diesel(sql_type = sql_types::Bool)]
+    reachable: bool,
+}
+
+/// A dependent server and one of the servers it depends on.
+#[derive(QueryableByName)]
+struct DependentDB {
+    #[diesel(sql_type = sql_types::Uuid)]
+    id: Uuid,
+    #[diesel(sql_type = sql_types::Varchar)]
+    name: String,
+    #[diesel(sql_type = sql_types::Inet)]
+    ip: IpNetwork,
+    #[diesel(sql_type = sql_types::Uuid)]
+    depends_on_id: Uuid,
+}
+
+/// Whether `server_id` depends on `other_id`, directly or through other servers.
+fn depends_on(conn: &mut PgConnection, server_id: Uuid, other_id: Uuid) -> QueryResult<bool> {
+    diesel::sql_query(
+        "WITH RECURSIVE reachable(id) AS ( \
+             SELECT depends_on_id FROM server_dependencies WHERE server_id = $1 \
+             UNION \
+             SELECT d.depends_on_id FROM server_dependencies d \
+                 JOIN reachable r ON d.server_id = r.id \
+         ) \
+         SELECT EXISTS (SELECT 1 FROM reachable WHERE id = $2) AS reachable",
+    )
+    .bind::<sql_types::Uuid, _>(server_id)
+    .bind::<sql_types::Uuid, _>(other_id)
+    .get_result::<Reachable>(conn)
+    .map(|row| row.reachable)
+}
+
+/// Records that one of the owner's servers depends on another of them. An edge
+/// that would close a cycle is rejected; the table lock keeps two concurrent
+/// inserts from closing one together.
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    new_dependency: NewServerDependencyDB,
+) -> Result<DependencyModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        conn.transaction(|conn| {
+            let server_id = new_dependency.server_id;
+            let depends_on_id = new_dependency.depends_on_id;
+            check_live_owned(conn, owner_id, server_id)?;
+            check_live_owned(conn, owner_id, depends_on_id)?;
+            if server_id == depends_on_id {
+                return Err(InfraError::Invalid(String::from(
+                    "A server cannot depend on itself",
+                )));
+            }
+
+            diesel::sql_query("LOCK TABLE server_dependencies IN SHARE ROW EXCLUSIVE MODE")
+                .execute(conn)?;
+            if depends_on(conn, depends_on_id, server_id)? {
+                return Err(InfraError::Invalid(String::from(
+                    "The other server already depends on this one, the dependency would form a cycle",
+                )));
+            }
+
+            let created_at = diesel::insert_into(server_dependencies::table)
+                .values(new_dependency)
+                .returning(server_dependencies::created_at)
+                .get_result(conn)?;
+            let (name, ip) = servers::table
+                .filter(servers::id.eq(depends_on_id))
+                .select((servers::name, servers::ip))
+                .get_result::<(String, IpNetwork)>(conn)?;
+
+            Ok(DependencyModel {
+                depends_on: depends_on_id,
+                name,
+                ip,
+                created_at,
+            })
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// The live servers one of the owner's servers depends on directly, by name.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<DependencyModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_dependencies::table
+                .inner_join(servers::table.on(servers::id.eq(server_dependencies::depends_on_id)))
+                .filter(server_dependencies::server_id.eq(server_id))
+                .filter(servers::deleted_at.is_null())
+                .order((servers::name.asc(), servers::id.asc()))
+                .select((
+                    servers::id,
+                    servers::name,
+                    servers::ip,
+                    server_dependencies::created_at,
+                ))
+                .load::<(Uuid, String, IpNetwork, chrono::DateTime<chrono::Utc>)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res
+        .into_iter()
+        .map(|(depends_on, name, ip, created_at)| DependencyModel {
+            depends_on,
+            name,
+            ip,
+            created_at,
+        })
+        .collect())
+}
+
+/// Live servers that depend on one of the owner's servers, ordered by distance
+/// and name. Without `transitive` only direct dependents are returned; with it
+/// every server reached through live dependents, at its shortest distance.
+pub async fn get_dependents(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    transitive: bool,
+) -> Result<Vec<DependentModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    // The recursion only collects the ids, so every server is visited once;
+    // the distances are worked out from the edges between them afterwards.
+    let edges = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::sql_query(
+                "WITH RECURSIVE dependents(id) AS ( \
+                     SELECT d.server_id FROM server_dependencies d \
+                         JOIN servers s ON s.id = d.server_id AND s.deleted_at IS NULL \
+                         WHERE d.depends_on_id = $1 \
+                     UNION \
+                     SELECT d.server_id FROM server_dependencies d \
+                         JOIN dependents r ON d.depends_on_id = r.id \
+                         JOIN servers s ON s.id = d.server_id AND s.deleted_at IS NULL \
+                         WHERE $2 \
+                 ) \
+                 SELECT s.id, s.name, s.ip, d.depends_on_id \
+                 FROM dependents r \
+                 JOIN servers s ON s.id = r.id \
+                 JOIN server_dependencies d ON d.server_id = r.id \
+                 WHERE d.depends_on_id = $1 OR d.depends_on_id IN (SELECT id FROM dependents)",
+            )
+            .bind::<sql_types::Uuid, _>(server_id)
+            .bind::<sql_types::Bool, _>(transitive)
+            .load::<DependentDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    let mut by_depends_on: HashMap<Uuid, Vec<DependentDB>> = HashMap::new();
+    for edge in edges {
+        by_depends_on
+            .entry(edge.depends_on_id)
+            .or_default()
+            .push(edge);
+    }
+
+    // Breadth first from `server_id`, a server is placed at its shortest distance.
+    let mut dependents = Vec::new();
+    let mut seen = HashSet::from([server_id]);
+    let mut level = vec![server_id];
+    let mut depth = 0;
+    while !level.is_empty() {
+        depth += 1;
+        let mut next_level = Vec::new();
+        for depends_on in level {
+            for edge in by_depends_on.remove(&depends_on).unwrap_or_default() {
+                if seen.insert(edge.id) {
+                    next_level.push(edge.id);
+                    dependents.push(DependentModel {
+                        id: edge.id,
+                        name: edge.name,
+                        ip: edge.ip,
+                        depends_on,
+                        depth,
+                    });
+                }
+            }
+        }
+        level = next_level;
+    }
+
+    dependents.sort_by(|a, b| (a.depth, &a.name).cmp(&(b.depth, &b.name)));
+    Ok(dependents)
+}
+
+/// Names of the live servers depending directly on `server_id`.
+pub fn dependent_names(conn: &mut PgConnection, server_id: Uuid) -> QueryResult<Vec<String>> {
+    server_dependencies::table
+        .inner_join(servers::table.on(servers::id.eq(server_dependencies::server_id)))
+        .filter(server_dependencies::depends_on_id.eq(server_id))
+        .filter(servers::deleted_at.is_null())
+        .order(servers::name.asc())
+        .select(servers::name)
+        .load(conn)
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    depends_on_id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::delete(
+                server_dependencies::table
+                    .filter(server_dependencies::server_id.eq(server_id))
+                    .filter(server_dependencies::depends_on_id.eq(depends_on_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{deserialize::Queryable, Selectable};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::server_dns::DnsCheckModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::schema::{server_dns_checks, servers};
+
+#[derive(Queryable, Selectable, Insertable)]
+#[diesel(table_name = server_dns_checks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerDnsCheckDB {
+    pub server_id: Uuid,
+    pub hostname: Option<String>,
+    pub ip: IpNetwork,
+    pub addresses: Vec<IpNetwork>,
+    pub forward_error: Option<String>,
+    pub ptr_names: Vec<String>,
+    pub reverse_error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl ServerDnsCheckDB {
+    pub fn new(server_id: Uuid, check: DnsCheckModel) -> Self {
+        ServerDnsCheckDB {
+            server_id,
+            hostname: check.hostname,
+            ip: check.ip,
+            addresses: check.addresses.into_iter().map(IpNetwork::from).collect(),
+            forward_error: check.forward_error,
+            ptr_names: check.ptr_names,
+            reverse_error: check.reverse_error,
+            checked_at: check.checked_at,
+        }
+    }
+}
+
+/// A live server the DNS checker should look up.
+#[derive(Queryable)]
+pub struct DnsTargetDB {
+    pub server_id: Uuid,
+    pub ip: IpNetwork,
+    pub hostname: Option<String>,
+}
+
+/// Live servers of every owner, or only `server_ids` when given.
+pub async fn get_targets(
+    pool: &deadpool_diesel::postgres::Pool,
+    server_ids: Option<Vec<Uuid>>,
+) -> Result<Vec<DnsTargetDB>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            let mut query = servers::table
+                .filter(servers::deleted_at.is_null())
+                .into_boxed();
+            if let Some(server_ids) = server_ids {
+                query = query.filter(servers::id.eq_any(server_ids));
+            }
+            query
+                .select((servers::id, servers::ip, servers::hostname))
+                .load::<DnsTargetDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+/// Replaces the latest DNS check of each server. Servers purged while they
+/// were being looked up are skipped.
+pub async fn save(
+    pool: &deadpool_diesel::postgres::Pool,
+    checks: Vec<ServerDnsCheckDB>,
+) -> Result<usize, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            let server_ids: Vec<Uuid> = checks.iter().map(|check| check.server_id).collect();
+            let existing: Vec<Uuid> = servers::table
+                .filter(servers::id.eq_any(server_ids))
+                .select(servers::id)
+                .load(conn)?;
+            let checks: Vec<ServerDnsCheckDB> = checks
+                .into_iter()
+                .filter(|check| existing.contains(&check.server_id))
+                .collect();
+
+            diesel::insert_into(server_dns_checks::table)
+                .values(checks)
+                .on_conflict(server_dns_checks::server_id)
+                .do_update()
+                .set((
+                    server_dns_checks::hostname.eq(excluded(server_dns_checks::hostname)),
+                    server_dns_checks::ip.eq(excluded(server_dns_checks::ip)),
+                    server_dns_checks::addresses.eq(excluded(server_dns_checks::addresses)),
+                    server_dns_checks::forward_error.eq(excluded(server_dns_checks::forward_error)),
+                    server_dns_checks::ptr_names.eq(excluded(server_dns_checks::ptr_names)),
+                    server_dns_checks::reverse_error.eq(excluded(server_dns_checks::reverse_error)),
+                    server_dns_checks::checked_at.eq(excluded(server_dns_checks::checked_at)),
+                ))
+                .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+pub async fn get_latest(
+    pool: &deadpool_diesel::postgres::Pool,
+    server_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, DnsCheckModel>, InfraError> {
+    if server_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            server_dns_checks::table
+                .filter(server_dns_checks::server_id.eq_any(server_ids))
+                .select(ServerDnsCheckDB::as_select())
+                .load::<ServerDnsCheckDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res
+        .into_iter()
+        .map(|check_db| {
+            (
+                check_db.server_id,
+                adapt_dns_check_db_to_dns_check(check_db),
+            )
+        })
+        .collect())
+}
+
+fn adapt_dns_check_db_to_dns_check(check_db: ServerDnsCheckDB) -> DnsCheckModel {
+    DnsCheckModel {
+        hostname: check_db.hostname,
+        ip: check_db.ip,
+        addresses: check_db
+            .addresses
+            .into_iter()
+            .map(|address| address.ip())
+            .collect(),
+        forward_error: check_db.forward_error,
+        ptr_names: check_db.ptr_names,
+        reverse_error: check_db.reverse_error,
+        checked_at: check_db.checked_at,
+    }
+}
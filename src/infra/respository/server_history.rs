@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::server::ServerModel;
+use crate::domains::models::server_history::{ServerChangeAction, ServerRevisionModel};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::schema::{server_history, servers, users};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_history)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerHistoryDB {
+    pub revision: i32,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_history)]
+pub struct NewServerHistoryDB {
+    pub server_id: Uuid,
+    pub revision: i32,
+    pub action: String,
+    pub actor_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// Appends the next revision of `server_id`. Must run inside the transaction
+/// that made the change so history and the row never disagree.
+pub fn record(
+    conn: &mut PgConnection,
+    server_id: Uuid,
+    actor_id: Uuid,
+    action: ServerChangeAction,
+    before: Option<&ServerModel>,
+    after: Option<&ServerModel>,
+) -> QueryResult<()> {
+    let last_revision = server_history::table
+        .filter(server_history::server_id.eq(server_id))
+        .select(diesel::dsl::max(server_history::revision))
+        .get_result::<Option<i32>>(conn)?;
+
+    let new_revision = NewServerHistoryDB {
+        server_id,
+        revision: last_revision.unwrap_or(0) + 1,
+        action: action.as_str().to_string(),
+        actor_id: Some(actor_id),
+        before: before.and_then(|server| serde_json::to_value(server).ok()),
+        after: after.and_then(|server| serde_json::to_value(server).ok()),
+    };
+
+    diesel::insert_into(server_history::table)
+        .values(new_revision)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// All revisions of one of the owner's servers, oldest first. Trashed servers
+/// keep their history.
+pub async fn get_history(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<ServerRevisionModel>, InfraError> {
+    get_revisions(pool, owner_id, server_id, None).await
+}
+
+/// The requested revisions of one of the owner's servers, oldest first.
+pub async fn get_revisions(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    revisions: Option<Vec<i32>>,
+) -> Result<Vec<ServerRevisionModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let owned = diesel::select(diesel::dsl::exists(
+                servers::table
+                    .filter(servers::id.eq(server_id))
+                    .filter(servers::owner_id.eq(owner_id)),
+            ))
+            .get_result::<bool>(conn)?;
+
+            if !owned {
+                return Err(diesel::result::Error::NotFound);
+            }
+
+            let mut query = server_history::table
+                .left_join(users::table)
+                .filter(server_history::server_id.eq(server_id))
+                .into_boxed();
+
+            if let Some(revisions) = revisions {
+                query = query.filter(server_history::revision.eq_any(revisions));
+            }
+
+            query
+                .order(server_history::revision.asc())
+                .select((ServerHistoryDB::as_select(), users::username.nullable()))
+                .load::<(ServerHistoryDB, Option<String>)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res
+        .into_iter()
+        .filter_map(|(history_db, actor_username)| {
+            adapt_server_history_db_to_revision(history_db, actor_username)
+        })
+        .collect())
+}
+
+fn adapt_server_history_db_to_revision(
+    history_db: ServerHistoryDB,
+    actor_username: Option<String>,
+) -> Option<ServerRevisionModel> {
+    Some(ServerRevisionModel {
+        revision: history_db.revision,
+        action: ServerChangeAction::parse(&history_db.action)?,
+        actor_id: history_db.actor_id,
+        actor_username,
+        changed_at: history_db.changed_at,
+        before: history_db
+            .before
+            .and_then(|before| serde_json::from_value(before).ok()),
+        after: history_db
+            .after
+            .and_then(|after| serde_json::from_value(after).ok()),
+    })
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::schema::{server_pins, servers};
+
+/// Pins one of the user's servers; pinning it again keeps the first `pinned_at`.
+pub async fn pin(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+    server_id: Uuid,
+) -> Result<DateTime<Utc>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, user_id, server_id)?;
+
+            diesel::insert_into(server_pins::table)
+                .values((
+                    server_pins::user_id.eq(user_id),
+                    server_pins::server_id.eq(server_id),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            server_pins::table
+                .filter(server_pins::user_id.eq(user_id))
+                .filter(server_pins::server_id.eq(server_id))
+                .select(server_pins::pinned_at)
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+pub async fn unpin(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+    server_id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            diesel::delete(
+                server_pins::table
+                    .filter(server_pins::user_id.eq(user_id))
+                    .filter(server_pins::server_id.eq(server_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+/// The user's pinned live servers, most recently pinned first.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+) -> Result<Vec<(Uuid, DateTime<Utc>)>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            server_pins::table
+                .inner_join(servers::table)
+                .filter(server_pins::user_id.eq(user_id))
+                .filter(servers::deleted_at.is_null())
+                .order(server_pins::pinned_at.desc())
+                .select((server_pins::server_id, server_pins::pinned_at))
+                .load::<(Uuid, DateTime<Utc>)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
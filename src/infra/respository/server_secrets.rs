@@ -0,0 +1,252 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::upsert::excluded;
+use diesel::{deserialize::Queryable, dsl, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::secret::SecretModel;
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::infra::vault::Sealed;
+use crate::schema::{server_secret_reveals, server_secrets, users};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_secrets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SecretDB {
+    pub server_id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_secrets)]
+pub struct NewSecretDB {
+    pub server_id: Uuid,
+    pub name: String,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub created_by: Uuid,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_secret_reveals)]
+struct NewRevealDB {
+    server_id: Uuid,
+    secret_name: String,
+    actor_id: Uuid,
+    succeeded: bool,
+}
+
+/// Stores a secret of one of the owner's servers, replacing the value of an
+/// existing one with the same name.
+pub async fn upsert(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    new_secret: NewSecretDB,
+) -> Result<SecretModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, new_secret.server_id)?;
+
+            diesel::insert_into(server_secrets::table)
+                .values(new_secret)
+                .on_conflict((server_secrets::server_id, server_secrets::name))
+                .do_update()
+                .set((
+                    server_secrets::nonce.eq(excluded(server_secrets::nonce)),
+                    server_secrets::ciphertext.eq(excluded(server_secrets::ciphertext)),
+                    server_secrets::updated_at.eq(dsl::now),
+                ))
+                .returning(SecretDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_secret_db_to_secret(res))
+}
+
+/// The secrets of one of the owner's servers by name, without their values.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<SecretModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_secrets::table
+                .filter(server_secrets::server_id.eq(server_id))
+                .order(server_secrets::name.asc())
+                .select(SecretDB::as_select())
+                .load::<SecretDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res.into_iter().map(adapt_secret_db_to_secret).collect())
+}
+
+/// The sealed value of a secret, for a reveal.
+pub async fn get_sealed(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    name: String,
+) -> Result<Sealed, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let (nonce, ciphertext) = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_secrets::table
+                .filter(server_secrets::server_id.eq(server_id))
+                .filter(server_secrets::name.eq(name))
+                .select((server_secrets::nonce, server_secrets::ciphertext))
+                .get_result::<(Vec<u8>, Vec<u8>)>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(Sealed { nonce, ciphertext })
+}
+
+/// Writes the audit record of an attempt to reveal a secret.
+pub async fn record_reveal(
+    pool: &deadpool_diesel::postgres::Pool,
+    actor_id: Uuid,
+    server_id: Uuid,
+    name: String,
+    succeeded: bool,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        diesel::insert_into(server_secret_reveals::table)
+            .values(NewRevealDB {
+                server_id,
+                secret_name: name,
+                actor_id,
+                succeeded,
+            })
+            .execute(conn)
+    })
+    .await
+    .map_err(adapt_infra_error)?
+    .map_err(adapt_infra_error)?;
+
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RevealCheck {
+    Verified,
+    WrongPassword,
+    LockedOut,
+}
+
+/// Checks the password for a reveal unless `actor_id` already failed
+/// `max_failed` times since `since`, across all servers. A wrong password is
+/// recorded right away. The user row stays locked from the count until then,
+/// so concurrent attempts of one user cannot all slip under the limit.
+pub async fn check_reveal(
+    pool: &deadpool_diesel::postgres::Pool,
+    actor_id: Uuid,
+    server_id: Uuid,
+    name: String,
+    pw: String,
+    since: DateTime<Utc>,
+    max_failed: i64,
+) -> Result<RevealCheck, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                let pw_hash = users::table
+                    .filter(users::id.eq(actor_id))
+                    .select(users::pw_hash)
+                    .for_update()
+                    .get_result::<Vec<u8>>(conn)?;
+
+                let failed = server_secret_reveals::table
+                    .filter(server_secret_reveals::actor_id.eq(actor_id))
+                    .filter(server_secret_reveals::succeeded.eq(false))
+                    .filter(server_secret_reveals::revealed_at.gt(since))
+                    .count()
+                    .get_result::<i64>(conn)?;
+                if failed >= max_failed {
+                    return Ok(RevealCheck::LockedOut);
+                }
+
+                let stored_pw_hash = String::from_utf8_lossy(&pw_hash).to_string();
+                if bcrypt::verify(&pw, &stored_pw_hash).unwrap_or(false) {
+                    return Ok(RevealCheck::Verified);
+                }
+
+                diesel::insert_into(server_secret_reveals::table)
+                    .values(NewRevealDB {
+                        server_id,
+                        secret_name: name,
+                        actor_id,
+                        succeeded: false,
+                    })
+                    .execute(conn)?;
+                Ok::<_, diesel::result::Error>(RevealCheck::WrongPassword)
+            })
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    name: String,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::delete(
+                server_secrets::table
+                    .filter(server_secrets::server_id.eq(server_id))
+                    .filter(server_secrets::name.eq(name)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+fn adapt_secret_db_to_secret(secret_db: SecretDB) -> SecretModel {
+    SecretModel {
+        server_id: secret_db.server_id,
+        name: secret_db.name,
+        created_at: secret_db.created_at,
+        updated_at: secret_db.updated_at,
+    }
+}
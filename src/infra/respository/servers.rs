@@ -1,32 +1,250 @@
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use diesel::*;
 use diesel::{deserialize::Queryable, Selectable};
+use ipnetwork::IpNetwork;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domains::models::server::ServerModel;
+use crate::domains::models::labels::{
+    deserialize_optional_label_selector, LabelRequirement, LabelSelector, Labels,
+};
+use crate::domains::models::maintenance::active_maintenance;
+use crate::domains::models::metadata::{
+    deserialize_metadata_filters, validate_metadata, Metadata, MetadataRequirement,
+    METADATA_FILTER_PREFIX,
+};
+use crate::domains::models::server::{
+    deserialize_optional_server_address, format_server_address, AddressUniqueness, ServerModel,
+};
+use crate::domains::models::server_history::ServerChangeAction;
 use crate::infra::errors::{adapt_infra_error, InfraError};
-use crate::schema::servers;
+use crate::infra::respository::{
+    maintenance, metadata_schemas, server_checks, server_dependencies, server_history,
+};
+use crate::schema::{server_pins, server_services, servers};
 
 #[derive(Serialize, Queryable, Selectable)]
 #[diesel(table_name = servers)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct ServerDB {
     pub id: Uuid,
-    pub ip: String,
+    pub ip: IpNetwork,
     pub name: String,
+    pub version: i32,
+    pub labels: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub check_ports: Vec<i32>,
+    pub metadata: serde_json::Value,
+    pub hostname: Option<String>,
 }
 
 #[derive(Deserialize, Insertable)]
 #[diesel(table_name = servers)]
 pub struct NewServerDB {
     pub name: String,
-    pub ip: String,
+    pub ip: IpNetwork,
+    pub owner_id: Uuid,
+    pub labels: serde_json::Value,
+    pub check_ports: Vec<i32>,
+    pub metadata: serde_json::Value,
+    pub hostname: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct ServersFilter {
     name_contains: Option<String>,
-    ip_contains: Option<String>,
+    /// Matches servers whose address lies inside this subnet, e.g. `10.0.0.0/8`.
+    #[serde(default, deserialize_with = "deserialize_optional_server_address")]
+    ip_in: Option<IpNetwork>,
+    /// Kubernetes style selector, e.g. `env=prod,role!=cache,team in (a,b)`.
+    #[serde(default, deserialize_with = "deserialize_optional_label_selector")]
+    label_selector: Option<LabelSelector>,
+    /// Case-insensitive fuzzy search over name, ip and labels.
+    q: Option<String>,
+    /// Servers running a service of this name, case-insensitive.
+    service: Option<String>,
+    /// `meta.<path>=<value>` parameters, e.g. `meta.rack=R12`.
+    #[serde(flatten, deserialize_with = "deserialize_metadata_filters")]
+    meta: Vec<MetadataRequirement>,
+}
+
+impl ServersFilter {
+    const PARAMS: [&'static str; 5] = ["name_contains", "ip_in", "label_selector", "q", "service"];
+
+    /// Reads a filter kept as query parameters, e.g. by a saved view. Unlike
+    /// a request, unknown parameters are refused rather than ignored.
+    pub fn from_params(params: &BTreeMap<String, String>) -> Result<Self, String> {
+        if let Some(unknown) = params.keys().find(|key| {
+            !Self::PARAMS.contains(&key.as_str()) && !key.starts_with(METADATA_FILTER_PREFIX)
+        }) {
+            return Err(format!(
+                "`{}` is not a server filter, use {}, or `{}<path>`",
+                unknown,
+                Self::PARAMS.map(|param| format!("`{}`", param)).join(", "),
+                METADATA_FILTER_PREFIX
+            ));
+        }
+        serde_json::to_value(params)
+            .and_then(serde_json::from_value)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Whether no filter parameter was given.
+    pub fn is_empty(&self) -> bool {
+        self.name_contains.is_none()
+            && self.ip_in.is_none()
+            && self.label_selector.is_none()
+            && self.q.is_none()
+            && self.service.is_none()
+            && self.meta.is_empty()
+    }
+
+    fn search(&self) -> Option<String> {
+        self.q
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_lowercase)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServersSort {
+    #[default]
+    Name,
+    Ip,
+    CreatedAt,
+    /// Best `q` matches first; the default while searching.
+    Relevance,
+}
+
+/// Keyset pagination over `(sort column, id)`. When `after` is given the sort
+/// order stored in the cursor wins over `sort`.
+#[derive(Deserialize)]
+pub struct ServersPagination {
+    limit: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_optional_cursor")]
+    after: Option<ServerCursor>,
+    sort: Option<ServersSort>,
+    /// Puts the servers the owner pinned before all others.
+    #[serde(skip)]
+    pinned_first: bool,
+}
+
+impl ServersPagination {
+    const DEFAULT_LIMIT: i64 = 50;
+    const MAX_LIMIT: i64 = 500;
+
+    /// Largest pages in `sort` order, for walking a whole listing.
+    pub fn walk(sort: Option<ServersSort>) -> Self {
+        ServersPagination {
+            limit: Some(Self::MAX_LIMIT),
+            after: None,
+            sort,
+            pinned_first: false,
+        }
+    }
+
+    pub fn pinned_first(self) -> Self {
+        ServersPagination {
+            pinned_first: true,
+            ..self
+        }
+    }
+
+    /// The page following the one that returned `next_cursor`.
+    pub fn next_page(&self, next_cursor: &str) -> Option<Self> {
+        Some(ServersPagination {
+            limit: self.limit,
+            after: Some(ServerCursor::decode(next_cursor)?),
+            sort: self.sort,
+            pinned_first: self.pinned_first,
+        })
+    }
+
+    fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT)
+    }
+
+    fn sort(&self, searching: bool) -> ServersSort {
+        let sort = self
+            .after
+            .as_ref()
+            .map(|after| after.sort)
+            .or(self.sort)
+            .unwrap_or(if searching {
+                ServersSort::Relevance
+            } else {
+                ServersSort::Name
+            });
+
+        match sort {
+            ServersSort::Relevance if !searching => ServersSort::Name,
+            sort => sort,
+        }
+    }
+}
+
+/// Opaque position in a sorted listing: the sort column value and id of the
+/// last row of the previous page, and whether it was pinned.
+#[derive(Serialize, Deserialize)]
+pub struct ServerCursor {
+    sort: ServersSort,
+    key: String,
+    id: Uuid,
+    #[serde(default)]
+    pinned: bool,
+}
+
+impl ServerCursor {
+    fn after(sort: ServersSort, server_db: &ServerDB, rank: f32, pinned: bool) -> Self {
+        let key = match sort {
+            ServersSort::Relevance => rank.to_string(),
+            ServersSort::Name => server_db.name.clone(),
+            ServersSort::Ip => server_db.ip.to_string(),
+            ServersSort::CreatedAt => server_db.created_at.to_rfc3339(),
+        };
+        ServerCursor {
+            sort,
+            key,
+            id: server_db.id,
+            pinned,
+        }
+    }
+
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(value: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(value).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+fn deserialize_optional_cursor<'de, D>(deserializer: D) -> Result<Option<ServerCursor>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => ServerCursor::decode(&value)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom("`after` is not a valid cursor")),
+        None => Ok(None),
+    }
+}
+
+pub struct ServersPage {
+    pub servers: Vec<ServerModel>,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -40,10 +258,16 @@ pub struct DeleteByName {
     pub name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize, AsChangeset)]
+#[diesel(table_name = servers)]
 pub struct UpdateServer {
-    pub name: String,
-    pub ip: String,
+    pub name: Option<String>,
+    pub ip: Option<IpNetwork>,
+    pub labels: Option<serde_json::Value>,
+    pub check_ports: Option<Vec<i32>>,
+    pub metadata: Option<serde_json::Value>,
+    /// `Some(None)` clears the hostname.
+    pub hostname: Option<Option<String>>,
 }
 
 #[derive(Serialize)]
@@ -51,52 +275,684 @@ pub struct DeleteServer {
     pub id: i32,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkMode {
+    /// The first failing item rolls back the whole batch.
+    #[default]
+    AllOrNothing,
+    /// Every item runs in its own savepoint; failed items are reported and skipped.
+    BestEffort,
+}
+
+pub enum BulkOutcome<T> {
+    /// One result per item, in input order. The successful ones were committed.
+    Committed(Vec<Result<T, InfraError>>),
+    /// Item `index` failed in `AllOrNothing` mode, nothing was committed.
+    Aborted { index: usize, error: InfraError },
+}
+
 pub async fn insert(
     pool: &deadpool_diesel::postgres::Pool,
     new_server: NewServerDB,
+    uniqueness: AddressUniqueness,
 ) -> Result<ServerModel, InfraError> {
     let conn = pool.get().await.map_err(adapt_infra_error)?;
 
-    let res = conn
-        .interact(|conn| {
-            diesel::insert_into(servers::table)
-                .values(new_server)
-                .returning(ServerDB::as_returning())
-                .get_result(conn)
-        })
+    conn.interact(move |conn| conn.transaction(|conn| insert_one(conn, new_server, uniqueness)))
         .await
         .map_err(adapt_infra_error)?
-        .map_err(adapt_infra_error)?;
+}
+
+/// Inserts all `new_servers` over a single connection and transaction.
+pub async fn insert_many(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_servers: Vec<NewServerDB>,
+    mode: BulkMode,
+    uniqueness: AddressUniqueness,
+) -> Result<BulkOutcome<ServerModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        run_bulk(conn, new_servers, mode, |conn, new_server| {
+            insert_one(conn, new_server, uniqueness)
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// Inserts one server within the caller's transaction.
+pub fn insert_one(
+    conn: &mut PgConnection,
+    new_server: NewServerDB,
+    uniqueness: AddressUniqueness,
+) -> Result<ServerModel, InfraError> {
+    let actor_id = new_server.owner_id;
+    let server_db = diesel::insert_into(servers::table)
+        .values(new_server)
+        .returning(ServerDB::as_returning())
+        .get_result(conn)?;
+    check_metadata(conn, &server_db)?;
+    check_address(conn, actor_id, &server_db, uniqueness)?;
+
+    let server_id = server_db.id;
+    let server = adapt_server_db_to_server(server_db);
+    server_history::record(
+        conn,
+        server_id,
+        actor_id,
+        ServerChangeAction::Insert,
+        None,
+        Some(&server),
+    )?;
 
-    Ok(adapt_server_db_to_server(res))
+    Ok(server)
+}
+
+fn run_bulk<I, T>(
+    conn: &mut PgConnection,
+    items: Vec<I>,
+    mode: BulkMode,
+    apply: impl Fn(&mut PgConnection, I) -> Result<T, InfraError>,
+) -> Result<BulkOutcome<T>, InfraError> {
+    match mode {
+        BulkMode::AllOrNothing => {
+            let mut failed_index = None;
+            let res = conn.transaction(|conn| {
+                let mut results = Vec::with_capacity(items.len());
+                for (index, item) in items.into_iter().enumerate() {
+                    match apply(conn, item) {
+                        Ok(value) => results.push(Ok(value)),
+                        Err(error) => {
+                            failed_index = Some(index);
+                            return Err(error);
+                        }
+                    }
+                }
+                Ok(results)
+            });
+
+            match (res, failed_index) {
+                (Ok(results), _) => Ok(BulkOutcome::Committed(results)),
+                (Err(error), Some(index)) => Ok(BulkOutcome::Aborted { index, error }),
+                (Err(error), None) => Err(error),
+            }
+        }
+        BulkMode::BestEffort => conn.transaction(|conn| {
+            Ok(BulkOutcome::Committed(
+                items
+                    .into_iter()
+                    .map(|item| conn.transaction(|conn| apply(conn, item)))
+                    .collect(),
+            ))
+        }),
+    }
 }
 
 pub async fn get_all(
     pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    filter: ServersFilter,
+    pagination: ServersPagination,
+) -> Result<ServersPage, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let search = filter.search();
+    let limit = pagination.limit();
+    let sort = pagination.sort(search.is_some());
+    let pinned_first = pagination.pinned_first;
+    let after = pagination.after;
+
+    let mut res = conn
+        .interact(move |conn| {
+            let mut query = filtered_query(owner_id, filter);
+            if pinned_first {
+                query = query.order(is_pinned(owner_id).desc());
+            }
+            // Past the cursor within its run of pinned or other servers.
+            let after_pinned = after
+                .as_ref()
+                .filter(|_| pinned_first)
+                .map(|after| after.pinned);
+
+            query = match sort {
+                ServersSort::Relevance => {
+                    let q = search.unwrap_or_default();
+                    let rank = || word_similarity(q.clone(), search_text());
+                    if let Some(after) = after {
+                        let after_rank = after.key.parse::<f32>().map_err(|_| invalid_cursor())?;
+                        query = query.filter(after_cursor(
+                            owner_id,
+                            after_pinned,
+                            Box::new(
+                                rank()
+                                    .lt(after_rank)
+                                    .or(rank().eq(after_rank).and(servers::id.gt(after.id))),
+                            ),
+                        ));
+                    }
+                    return Ok::<_, InfraError>(
+                        query
+                            .then_order_by((rank().desc(), servers::id.asc()))
+                            .limit(limit + 1)
+                            .select((ServerDB::as_select(), rank(), is_pinned(owner_id)))
+                            .load::<(ServerDB, f32, bool)>(conn)?,
+                    );
+                }
+                ServersSort::Name => {
+                    if let Some(after) = after {
+                        let name = after.key;
+                        query = query.filter(after_cursor(
+                            owner_id,
+                            after_pinned,
+                            Box::new(
+                                servers::name
+                                    .gt(name.clone())
+                                    .or(servers::name.eq(name).and(servers::id.gt(after.id))),
+                            ),
+                        ));
+                    }
+                    query.then_order_by((servers::name.asc(), servers::id.asc()))
+                }
+                ServersSort::Ip => {
+                    if let Some(after) = after {
+                        let ip = after
+                            .key
+                            .parse::<IpNetwork>()
+                            .map_err(|_| invalid_cursor())?;
+                        query = query.filter(after_cursor(
+                            owner_id,
+                            after_pinned,
+                            Box::new(
+                                servers::ip
+                                    .gt(ip)
+                                    .or(servers::ip.eq(ip).and(servers::id.gt(after.id))),
+                            ),
+                        ));
+                    }
+                    query.then_order_by((servers::ip.asc(), servers::id.asc()))
+                }
+                ServersSort::CreatedAt => {
+                    if let Some(after) = after {
+                        let created_at = after
+                            .key
+                            .parse::<DateTime<Utc>>()
+                            .map_err(|_| invalid_cursor())?;
+                        query = query.filter(after_cursor(
+                            owner_id,
+                            after_pinned,
+                            Box::new(
+                                servers::created_at.gt(created_at).or(servers::created_at
+                                    .eq(created_at)
+                                    .and(servers::id.gt(after.id))),
+                            ),
+                        ));
+                    }
+                    query.then_order_by((servers::created_at.asc(), servers::id.asc()))
+                }
+            };
+
+            Ok(query
+                .limit(limit + 1)
+                .select((ServerDB::as_select(), is_pinned(owner_id)))
+                .load::<(ServerDB, bool)>(conn)?
+                .into_iter()
+                .map(|(server, pinned)| (server, 0.0, pinned))
+                .collect())
+        })
+        .await
+        .map_err(adapt_infra_error)??;
+
+    let next_cursor = if res.len() as i64 > limit {
+        res.truncate(limit as usize);
+        res.last()
+            .map(|(last, rank, pinned)| ServerCursor::after(sort, last, *rank, *pinned).encode())
+    } else {
+        None
+    };
+
+    let mut servers: Vec<ServerModel> = res
+        .into_iter()
+        .map(|(server_db, _, pinned)| ServerModel {
+            pinned: Some(pinned),
+            ..adapt_server_db_to_server(server_db)
+        })
+        .collect();
+    attach_checks(pool, &mut servers).await?;
+    attach_maintenance(pool, &mut servers).await?;
+    Ok(ServersPage {
+        servers,
+        next_cursor,
+    })
+}
+
+/// Every server matching `filter`, loaded page by page in name order.
+pub async fn get_all_matching(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
     filter: ServersFilter,
 ) -> Result<Vec<ServerModel>, InfraError> {
+    let mut servers = Vec::new();
+    let mut pagination = Some(ServersPagination::walk(Some(ServersSort::Name)));
+    while let Some(page_request) = pagination {
+        let page = get_all(pool, owner_id, filter.clone(), page_request).await?;
+        servers.extend(page.servers);
+        pagination = page
+            .next_cursor
+            .as_deref()
+            .and_then(|cursor| ServersPagination::walk(Some(ServersSort::Name)).next_page(cursor));
+    }
+    Ok(servers)
+}
+
+/// A digest of the ids and versions of the servers matching `filter`. It
+/// changes whenever one of them is edited, trashed or restored, or another one
+/// starts matching, without loading the servers themselves.
+pub async fn fingerprint(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    filter: ServersFilter,
+) -> Result<String, InfraError> {
     let conn = pool.get().await.map_err(adapt_infra_error)?;
 
     let res = conn
         .interact(move |conn| {
-            let mut query = servers::table.into_boxed::<diesel::pg::Pg>();
+            filtered_query(owner_id, filter)
+                .select(dsl::sql::<sql_types::Text>(
+                    "md5(count(*) || ':' || coalesce(string_agg(servers.id || ':' || servers.version, ',' order by servers.id), ''))",
+                ))
+                .get_result::<String>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
 
-            if let Some(name_contains) = filter.name_contains {
-                query = query.filter(servers::name.like(format!("%{}%", name_contains)));
-            }
+    Ok(res)
+}
 
-            if let Some(ip_contains) = filter.ip_contains {
-                query = query.filter(servers::ip.like(format!("%{}%", ip_contains)));
-            }
+/// Best matches for the search box, at most `limit` of them.
+pub async fn suggest(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    q: String,
+    limit: i64,
+) -> Result<Vec<ServerModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let q = q.trim().to_lowercase();
+    let res = conn
+        .interact(move |conn| {
+            let query = servers::table
+                .filter(servers::owner_id.eq(owner_id))
+                .filter(servers::deleted_at.is_null())
+                .into_boxed::<diesel::pg::Pg>();
 
-            query.select(ServerDB::as_select()).load::<ServerDB>(conn)
+            filter_by_search(query, q.clone())
+                .order((
+                    word_similarity(q, search_text()).desc(),
+                    servers::name.asc(),
+                ))
+                .limit(limit)
+                .select(ServerDB::as_select())
+                .load::<ServerDB>(conn)
         })
         .await
         .map_err(adapt_infra_error)?
         .map_err(adapt_infra_error)?;
 
-    let servers: Vec<ServerModel> = res.into_iter().map(adapt_server_db_to_server).collect();
-    Ok(servers)
+    Ok(res.into_iter().map(adapt_server_db_to_server).collect())
+}
+
+fn filtered_query(owner_id: Uuid, filter: ServersFilter) -> BoxedServersQuery<'static> {
+    let search = filter.search();
+    let mut query = servers::table
+        .filter(servers::owner_id.eq(owner_id))
+        .filter(servers::deleted_at.is_null())
+        .into_boxed::<diesel::pg::Pg>();
+
+    if let Some(name_contains) = filter.name_contains {
+        query = query.filter(servers::name.like(format!("%{}%", name_contains)));
+    }
+
+    if let Some(ip_in) = filter.ip_in {
+        query = query.filter(servers::ip.is_contained_by_or_eq(ip_in));
+    }
+
+    if let Some(label_selector) = filter.label_selector {
+        for requirement in label_selector.requirements {
+            query = filter_by_label(query, requirement);
+        }
+    }
+
+    if let Some(service) = filter.service {
+        query = query.filter(diesel::dsl::exists(
+            server_services::table
+                .filter(server_services::server_id.eq(servers::id))
+                .filter(lower(server_services::name).eq(service.trim().to_lowercase())),
+        ));
+    }
+
+    for requirement in filter.meta {
+        query = query.filter(
+            servers::metadata
+                .retrieve_by_path_as_text(requirement.path)
+                .eq(requirement.value),
+        );
+    }
+
+    if let Some(q) = search {
+        query = filter_by_search(query, q);
+    }
+
+    query
+}
+
+type BoxedCondition =
+    Box<dyn BoxableExpression<servers::table, diesel::pg::Pg, SqlType = sql_types::Bool>>;
+
+#[dsl::auto_type]
+fn is_pinned(owner_id: Uuid) -> _ {
+    dsl::exists(
+        server_pins::table
+            .filter(server_pins::user_id.eq(owner_id))
+            .filter(server_pins::server_id.eq(servers::id)),
+    )
+}
+
+/// A cursor that decodes but whose key does not fit the sort it asks for.
+fn invalid_cursor() -> InfraError {
+    InfraError::Invalid(String::from("`after` is not a valid cursor for this sort"))
+}
+
+/// Rows after the cursor in sort order. With pinned servers first a listing
+/// is two runs, the pinned servers then the others, so a cursor within the
+/// pinned run also lets every other server through.
+fn after_cursor(
+    owner_id: Uuid,
+    after_pinned: Option<bool>,
+    key_after: BoxedCondition,
+) -> BoxedCondition {
+    match after_pinned {
+        None => key_after,
+        Some(true) => Box::new(
+            is_pinned(owner_id)
+                .and(key_after)
+                .or(dsl::not(is_pinned(owner_id))),
+        ),
+        Some(false) => Box::new(dsl::not(is_pinned(owner_id)).and(key_after)),
+    }
+}
+
+define_sql_function! {
+    /// Defined by the `server_search` migration and backed by a trigram index.
+    fn server_search_text(name: sql_types::Varchar, ip: sql_types::Inet, labels: sql_types::Jsonb) -> sql_types::Text;
+}
+
+define_sql_function! {
+    fn lower(value: sql_types::Varchar) -> sql_types::Varchar;
+}
+
+define_sql_function! {
+    fn word_similarity(needle: sql_types::Text, haystack: sql_types::Text) -> sql_types::Float4;
+}
+
+infix_operator!(WordSimilarTo, " <% ", backend: diesel::pg::Pg);
+
+fn search_text() -> server_search_text<servers::name, servers::ip, servers::labels> {
+    server_search_text(servers::name, servers::ip, servers::labels)
+}
+
+/// Substring or fuzzy word match of an already lower-cased `q`.
+fn filter_by_search(query: BoxedServersQuery<'_>, q: String) -> BoxedServersQuery<'_> {
+    let pattern = format!(
+        "%{}%",
+        q.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    query.filter(search_text().ilike(pattern).or(WordSimilarTo::new(
+        q.into_sql::<sql_types::Text>(),
+        search_text(),
+    )))
+}
+
+/// Applies `changes` only if the row is still at `expected_version`, bumping the
+/// version on success. A stale version yields `InfraError::Conflict`.
+pub async fn update(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+    expected_version: i32,
+    changes: UpdateServer,
+    uniqueness: AddressUniqueness,
+) -> Result<ServerModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        conn.transaction(|conn| {
+            let before = lock_owned(conn, owner_id, id, false)?;
+            if before.version != expected_version {
+                return Err(InfraError::Conflict);
+            }
+
+            let after = diesel::update(servers::table.filter(servers::id.eq(id)))
+                .set((&changes, servers::version.eq(servers::version + 1)))
+                .returning(ServerDB::as_returning())
+                .get_result(conn)?;
+            check_metadata(conn, &after)?;
+            if after.ip != before.ip {
+                check_address(conn, owner_id, &after, uniqueness)?;
+            }
+
+            record_change(conn, owner_id, ServerChangeAction::Update, before, after)
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// Sub-resources such as services can only be managed on the owner's live servers.
+pub fn check_live_owned(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> QueryResult<()> {
+    let owned = diesel::select(dsl::exists(
+        servers::table
+            .filter(servers::id.eq(server_id))
+            .filter(servers::owner_id.eq(owner_id))
+            .filter(servers::deleted_at.is_null()),
+    ))
+    .get_result::<bool>(conn)?;
+
+    if owned {
+        Ok(())
+    } else {
+        Err(diesel::result::Error::NotFound)
+    }
+}
+
+/// Locks one of the owner's servers for the rest of the transaction, either a
+/// live one or, with `trashed`, one that is in the trash.
+fn lock_owned(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    id: Uuid,
+    trashed: bool,
+) -> Result<ServerDB, InfraError> {
+    Ok(servers::table
+        .filter(servers::id.eq(id))
+        .filter(servers::owner_id.eq(owner_id))
+        .filter(servers::deleted_at.is_not_null().eq(trashed))
+        .select(ServerDB::as_select())
+        .for_update()
+        .get_result(conn)?)
+}
+
+fn record_change(
+    conn: &mut PgConnection,
+    actor_id: Uuid,
+    action: ServerChangeAction,
+    before: ServerDB,
+    after: ServerDB,
+) -> Result<ServerModel, InfraError> {
+    let server_id = after.id;
+    let before = adapt_server_db_to_server(before);
+    let after = adapt_server_db_to_server(after);
+
+    server_history::record(
+        conn,
+        server_id,
+        actor_id,
+        action,
+        Some(&before),
+        Some(&after),
+    )?;
+
+    Ok(after)
+}
+
+type BoxedServersQuery<'a> = servers::BoxedQuery<'a, diesel::pg::Pg>;
+
+fn filter_by_label(
+    query: BoxedServersQuery<'_>,
+    requirement: LabelRequirement,
+) -> BoxedServersQuery<'_> {
+    match requirement {
+        LabelRequirement::Equals(key, value) => {
+            query.filter(servers::labels.contains(label_json(key, value)))
+        }
+        LabelRequirement::NotEquals(key, value) => {
+            query.filter(dsl::not(servers::labels.contains(label_json(key, value))))
+        }
+        LabelRequirement::In(key, values) => {
+            query.filter(servers::labels.retrieve_as_text(key).eq_any(values))
+        }
+        LabelRequirement::NotIn(key, values) => values.into_iter().fold(query, |query, value| {
+            query.filter(dsl::not(
+                servers::labels.contains(label_json(key.clone(), value)),
+            ))
+        }),
+        LabelRequirement::Exists(key) => query.filter(servers::labels.has_key(key)),
+        LabelRequirement::NotExists(key) => query.filter(dsl::not(servers::labels.has_key(key))),
+    }
+}
+
+fn label_json(key: String, value: String) -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::from_iter([(
+        key,
+        serde_json::Value::String(value),
+    )]))
+}
+
+pub fn labels_to_json(labels: Labels) -> serde_json::Value {
+    serde_json::to_value(labels).unwrap_or_default()
+}
+
+pub fn metadata_to_json(metadata: Metadata) -> serde_json::Value {
+    serde_json::Value::Object(metadata)
+}
+
+pub fn ports_to_db(ports: Vec<u16>) -> Vec<i32> {
+    ports.into_iter().map(i32::from).collect()
+}
+
+async fn attach_checks(
+    pool: &deadpool_diesel::postgres::Pool,
+    servers: &mut [ServerModel],
+) -> Result<(), InfraError> {
+    let ids = servers
+        .iter()
+        .filter_map(|server| Uuid::parse_str(&server.id).ok())
+        .collect();
+    let mut checks = server_checks::get_latest(pool, ids).await?;
+    for server in servers {
+        server.check = Uuid::parse_str(&server.id)
+            .ok()
+            .and_then(|id| checks.remove(&id));
+    }
+    Ok(())
+}
+
+async fn attach_maintenance(
+    pool: &deadpool_diesel::postgres::Pool,
+    servers: &mut [ServerModel],
+) -> Result<(), InfraError> {
+    let now = Utc::now();
+    let ids = servers
+        .iter()
+        .filter_map(|server| Uuid::parse_str(&server.id).ok())
+        .collect();
+    let windows = maintenance::get_for_servers(pool, ids, now).await?;
+    for server in servers {
+        server.maintenance = Some(
+            Uuid::parse_str(&server.id)
+                .ok()
+                .and_then(|id| windows.get(&id))
+                .and_then(|windows| active_maintenance(windows, now)),
+        );
+    }
+    Ok(())
+}
+
+/// Validates the metadata of a row just written against the schemas selecting
+/// it; a violation rolls the change back.
+fn check_metadata(conn: &mut PgConnection, server_db: &ServerDB) -> Result<(), InfraError> {
+    let schemas = metadata_schemas::load_all(conn)?;
+    if schemas.is_empty() {
+        return Ok(());
+    }
+    let labels: Labels = serde_json::from_value(server_db.labels.clone()).unwrap_or_default();
+    let metadata: Metadata = serde_json::from_value(server_db.metadata.clone()).unwrap_or_default();
+    validate_metadata(&schemas, &labels, &metadata).map_err(InfraError::Invalid)
+}
+
+/// Fails with `InfraError::Duplicate` when another live server within
+/// `uniqueness` has the address of a row just written. The advisory lock keeps
+/// concurrent writers of the same address apart until commit, so at most one
+/// of them can pass.
+fn check_address(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    server_db: &ServerDB,
+    uniqueness: AddressUniqueness,
+) -> Result<(), InfraError> {
+    if uniqueness == AddressUniqueness::None {
+        return Ok(());
+    }
+
+    diesel::sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind::<sql_types::Text, _>(server_db.ip.to_string())
+        .execute(conn)?;
+
+    let mut query = servers::table
+        .filter(servers::ip.eq(server_db.ip))
+        .filter(servers::id.ne(server_db.id))
+        .filter(servers::deleted_at.is_null())
+        .into_boxed();
+    if uniqueness == AddressUniqueness::Owner {
+        query = query.filter(servers::owner_id.eq(owner_id));
+    }
+    let taken_by = query
+        .select((servers::name, servers::owner_id))
+        .first::<(String, Option<Uuid>)>(conn)
+        .optional()?;
+
+    match taken_by {
+        None => Ok(()),
+        Some((name, other_owner)) if other_owner == Some(owner_id) => {
+            Err(InfraError::Duplicate(format!(
+                "`{}` is already used by server `{}`",
+                format_server_address(&server_db.ip),
+                name
+            )))
+        }
+        Some(_) => Err(InfraError::Duplicate(format!(
+            "`{}` is already used by another server",
+            format_server_address(&server_db.ip)
+        ))),
+    }
 }
 
 fn adapt_server_db_to_server(server_db: ServerDB) -> ServerModel {
@@ -104,18 +960,246 @@ fn adapt_server_db_to_server(server_db: ServerDB) -> ServerModel {
         id: server_db.id.to_string(),
         name: server_db.name,
         ip: server_db.ip,
+        version: server_db.version,
+        labels: serde_json::from_value(server_db.labels).unwrap_or_default(),
+        created_at: server_db.created_at,
+        deleted_at: server_db.deleted_at,
+        check_ports: server_db
+            .check_ports
+            .into_iter()
+            .filter_map(|port| u16::try_from(port).ok())
+            .collect(),
+        metadata: serde_json::from_value(server_db.metadata).unwrap_or_default(),
+        hostname: server_db.hostname,
+        check: None,
+        services: None,
+        maintenance: None,
+        pinned: None,
     }
 }
 
-pub async fn delete(pool: &deadpool_diesel::postgres::Pool, id: Uuid) -> Result<(), InfraError> {
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportAction {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// Creates or updates one live server per row, matched by name. Every row runs
+/// in its own savepoint so a failing row leaves the others in place. With
+/// `dry_run` the whole import is rolled back and only the outcome is returned.
+pub async fn upsert_by_name(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_servers: Vec<NewServerDB>,
+    dry_run: bool,
+    uniqueness: AddressUniqueness,
+) -> Result<Vec<Result<(ImportAction, ServerModel), InfraError>>, InfraError> {
     let conn = pool.get().await.map_err(adapt_infra_error)?;
 
-    let _ = conn
+    conn.interact(move |conn| {
+        let mut preview = None;
+        let res = conn.transaction(|conn| {
+            let results = new_servers
+                .into_iter()
+                .map(|new_server| conn.transaction(|conn| upsert_one(conn, new_server, uniqueness)))
+                .collect();
+            if dry_run {
+                preview = Some(results);
+                return Err(InfraError::from(diesel::result::Error::RollbackTransaction));
+            }
+            Ok(results)
+        });
+
+        match preview {
+            Some(results) => Ok(results),
+            None => res,
+        }
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// Servers sharing the name make the row ambiguous and fail it with `Duplicate`.
+fn upsert_one(
+    conn: &mut PgConnection,
+    new_server: NewServerDB,
+    uniqueness: AddressUniqueness,
+) -> Result<(ImportAction, ServerModel), InfraError> {
+    let owner_id = new_server.owner_id;
+    let mut existing = servers::table
+        .filter(servers::owner_id.eq(owner_id))
+        .filter(servers::name.eq(&new_server.name))
+        .filter(servers::deleted_at.is_null())
+        .select(ServerDB::as_select())
+        .for_update()
+        .load::<ServerDB>(conn)?;
+
+    let before = match existing.len() {
+        0 => {
+            return Ok((
+                ImportAction::Created,
+                insert_one(conn, new_server, uniqueness)?,
+            ))
+        }
+        1 => existing.remove(0),
+        _ => {
+            return Err(InfraError::Duplicate(format!(
+                "Several servers are named `{}`, rename them first",
+                new_server.name
+            )))
+        }
+    };
+
+    if before.ip == new_server.ip && before.labels == new_server.labels {
+        return Ok((ImportAction::Unchanged, adapt_server_db_to_server(before)));
+    }
+
+    let after = diesel::update(servers::table.filter(servers::id.eq(before.id)))
+        .set((
+            servers::ip.eq(new_server.ip),
+            servers::labels.eq(new_server.labels),
+            servers::version.eq(servers::version + 1),
+        ))
+        .returning(ServerDB::as_returning())
+        .get_result(conn)?;
+    check_metadata(conn, &after)?;
+    if after.ip != before.ip {
+        check_address(conn, owner_id, &after, uniqueness)?;
+    }
+
+    let server = record_change(conn, owner_id, ServerChangeAction::Update, before, after)?;
+    Ok((ImportAction::Updated, server))
+}
+
+/// Moves the server to the trash; it stays restorable until purged. A server
+/// other live servers depend on is only moved with `force`.
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+    force: bool,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| conn.transaction(|conn| delete_one(conn, owner_id, id, force)))
+        .await
+        .map_err(adapt_infra_error)??;
+
+    Ok(())
+}
+
+/// Moves all `ids` to the trash over a single connection and transaction.
+pub async fn delete_many(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    ids: Vec<Uuid>,
+    mode: BulkMode,
+    force: bool,
+) -> Result<BulkOutcome<ServerModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        run_bulk(conn, ids, mode, |conn, id| {
+            delete_one(conn, owner_id, id, force)
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+fn delete_one(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    id: Uuid,
+    force: bool,
+) -> Result<ServerModel, InfraError> {
+    let before = lock_owned(conn, owner_id, id, false)?;
+    if !force {
+        let dependents = server_dependencies::dependent_names(conn, id)?;
+        if !dependents.is_empty() {
+            return Err(InfraError::InUse(format!(
+                "{} still depend on `{}`, pass force=true to delete it anyway",
+                dependents
+                    .iter()
+                    .map(|name| format!("`{}`", name))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                before.name
+            )));
+        }
+    }
+
+    let after = diesel::update(servers::table.filter(servers::id.eq(id)))
+        .set(servers::deleted_at.eq(dsl::now))
+        .returning(ServerDB::as_returning())
+        .get_result(conn)?;
+
+    record_change(conn, owner_id, ServerChangeAction::Delete, before, after)
+}
+
+pub async fn get_trash(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+) -> Result<Vec<ServerModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
         .interact(move |conn| {
-            diesel::delete(servers::table.filter(servers::id.eq(id))).execute(conn)
+            servers::table
+                .filter(servers::owner_id.eq(owner_id))
+                .filter(servers::deleted_at.is_not_null())
+                .order((servers::deleted_at.desc(), servers::id.asc()))
+                .select(ServerDB::as_select())
+                .load::<ServerDB>(conn)
         })
         .await
+        .map_err(adapt_infra_error)?
         .map_err(adapt_infra_error)?;
 
-    Ok(())
+    Ok(res.into_iter().map(adapt_server_db_to_server).collect())
+}
+
+pub async fn restore(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+    uniqueness: AddressUniqueness,
+) -> Result<ServerModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        conn.transaction(|conn| {
+            let before = lock_owned(conn, owner_id, id, true)?;
+
+            let after = diesel::update(servers::table.filter(servers::id.eq(id)))
+                .set(servers::deleted_at.eq(None::<DateTime<Utc>>))
+                .returning(ServerDB::as_returning())
+                .get_result(conn)?;
+            check_address(conn, owner_id, &after, uniqueness)?;
+
+            record_change(conn, owner_id, ServerChangeAction::Restore, before, after)
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// Hard-deletes every server that has been in the trash since before `deleted_before`.
+pub async fn purge_deleted(
+    pool: &deadpool_diesel::postgres::Pool,
+    deleted_before: DateTime<Utc>,
+) -> Result<usize, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let purged = conn
+        .interact(move |conn| {
+            diesel::delete(servers::table.filter(servers::deleted_at.lt(deleted_before)))
+                .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(purged)
 }
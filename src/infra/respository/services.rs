@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::service::{ServiceModel, ServiceProtocol};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::check_live_owned;
+use crate::schema::server_services;
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_services)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServiceDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub name: String,
+    pub port: i32,
+    pub protocol: String,
+    pub team: Option<String>,
+    pub url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_services)]
+pub struct NewServiceDB {
+    pub id: Uuid,
+    pub server_id: Uuid,
+    pub name: String,
+    pub port: i32,
+    pub protocol: String,
+    pub team: Option<String>,
+    pub url: Option<String>,
+}
+
+/// `team` and `url` are set to NULL with `Some(None)`.
+#[derive(AsChangeset, Default)]
+#[diesel(table_name = server_services)]
+pub struct UpdateServiceDB {
+    pub name: Option<String>,
+    pub port: Option<i32>,
+    pub protocol: Option<String>,
+    pub team: Option<Option<String>>,
+    pub url: Option<Option<String>>,
+}
+
+impl UpdateServiceDB {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.port.is_none()
+            && self.protocol.is_none()
+            && self.team.is_none()
+            && self.url.is_none()
+    }
+}
+
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    new_service: NewServiceDB,
+) -> Result<ServiceModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, new_service.server_id)?;
+
+            diesel::insert_into(server_services::table)
+                .values(new_service)
+                .returning(ServiceDB::as_returning())
+                .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_service_db_to_service(res))
+}
+
+/// The services of one of the owner's servers, by port.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+) -> Result<Vec<ServiceModel>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            server_services::table
+                .filter(server_services::server_id.eq(server_id))
+                .order((server_services::port.asc(), server_services::protocol.asc()))
+                .select(ServiceDB::as_select())
+                .load::<ServiceDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res.into_iter().map(adapt_service_db_to_service).collect())
+}
+
+/// Services of already authorized servers, grouped by server.
+pub async fn get_for_servers(
+    pool: &deadpool_diesel::postgres::Pool,
+    server_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, Vec<ServiceModel>>, InfraError> {
+    if server_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(|conn| {
+            server_services::table
+                .filter(server_services::server_id.eq_any(server_ids))
+                .order((server_services::port.asc(), server_services::protocol.asc()))
+                .select(ServiceDB::as_select())
+                .load::<ServiceDB>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    let mut services: HashMap<Uuid, Vec<ServiceModel>> = HashMap::new();
+    for service_db in res {
+        services
+            .entry(service_db.server_id)
+            .or_default()
+            .push(adapt_service_db_to_service(service_db));
+    }
+    Ok(services)
+}
+
+pub async fn update(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+    changes: UpdateServiceDB,
+) -> Result<ServiceModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::update(
+                server_services::table
+                    .filter(server_services::id.eq(id))
+                    .filter(server_services::server_id.eq(server_id)),
+            )
+            .set(&changes)
+            .returning(ServiceDB::as_returning())
+            .get_result(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(adapt_service_db_to_service(res))
+}
+
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            check_live_owned(conn, owner_id, server_id)?;
+
+            diesel::delete(
+                server_services::table
+                    .filter(server_services::id.eq(id))
+                    .filter(server_services::server_id.eq(server_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+fn adapt_service_db_to_service(service_db: ServiceDB) -> ServiceModel {
+    ServiceModel {
+        id: service_db.id,
+        name: service_db.name,
+        port: service_db.port as u16,
+        protocol: ServiceProtocol::parse(&service_db.protocol).unwrap_or_default(),
+        team: service_db.team,
+        url: service_db.url,
+        created_at: service_db.created_at,
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::{deserialize::Queryable, dsl, sql_types, Selectable};
+use ipnetwork::IpNetwork;
+use uuid::Uuid;
+
+use crate::domains::models::server::{AddressUniqueness, ServerModel};
+use crate::domains::models::subnet::{SubnetModel, SubnetUsage};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::servers::{insert_one, NewServerDB};
+use crate::schema::{servers, subnets};
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = subnets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SubnetDB {
+    pub id: Uuid,
+    pub cidr: IpNetwork,
+    pub gateway: Option<IpNetwork>,
+    pub vlan: Option<i32>,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = subnets)]
+pub struct NewSubnetDB {
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub cidr: IpNetwork,
+    pub gateway: Option<IpNetwork>,
+    pub vlan: Option<i32>,
+    pub description: Option<String>,
+}
+
+/// Single addresses of live servers inside any of `cidrs`, the owner's only
+/// unless addresses are unique across owners.
+fn used_addresses(
+    conn: &mut PgConnection,
+    owner_id: Uuid,
+    cidrs: Vec<IpNetwork>,
+    uniqueness: AddressUniqueness,
+) -> QueryResult<HashSet<IpAddr>> {
+    if cidrs.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let mut query = servers::table
+        .filter(
+            dsl::sql::<sql_types::Bool>("servers.ip <<= ANY(")
+                .bind::<sql_types::Array<sql_types::Inet>, _>(cidrs)
+                .sql(")"),
+        )
+        .filter(servers::deleted_at.is_null())
+        .into_boxed();
+    if uniqueness != AddressUniqueness::Global {
+        query = query.filter(servers::owner_id.eq(owner_id));
+    }
+
+    Ok(query
+        .select(servers::ip)
+        .load::<IpNetwork>(conn)?
+        .into_iter()
+        .filter(|ip| ip.prefix() == IpNetwork::from(ip.ip()).prefix())
+        .map(|ip| ip.ip())
+        .collect())
+}
+
+/// Subnets of one owner may not overlap, the table lock keeps two concurrent
+/// inserts from creating overlapping ones.
+pub async fn insert(
+    pool: &deadpool_diesel::postgres::Pool,
+    new_subnet: NewSubnetDB,
+) -> Result<SubnetModel, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction(|conn| {
+                diesel::sql_query("LOCK TABLE subnets IN SHARE ROW EXCLUSIVE MODE")
+                    .execute(conn)?;
+                let overlapping = subnets::table
+                    .filter(subnets::owner_id.eq(new_subnet.owner_id))
+                    .filter(subnets::cidr.overlaps_with(new_subnet.cidr))
+                    .select(subnets::cidr)
+                    .first::<IpNetwork>(conn)
+                    .optional()?;
+                if let Some(other) = overlapping {
+                    if other != new_subnet.cidr {
+                        return Err(InfraError::Duplicate(format!(
+                            "`{}` overlaps subnet `{}`",
+                            new_subnet.cidr, other
+                        )));
+                    }
+                }
+
+                Ok(diesel::insert_into(subnets::table)
+                    .values(new_subnet)
+                    .returning(SubnetDB::as_returning())
+                    .get_result(conn)?)
+            })
+        })
+        .await
+        .map_err(adapt_infra_error)??;
+
+    Ok(adapt_subnet_db_to_subnet(res))
+}
+
+/// The owner's subnets by range, with how much of each is in use.
+pub async fn get_all(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    uniqueness: AddressUniqueness,
+) -> Result<Vec<(SubnetModel, SubnetUsage)>, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let subnets = subnets::table
+                .filter(subnets::owner_id.eq(owner_id))
+                .order(subnets::cidr.asc())
+                .select(SubnetDB::as_select())
+                .load::<SubnetDB>(conn)?
+                .into_iter()
+                .map(adapt_subnet_db_to_subnet)
+                .collect::<Vec<_>>();
+            // One query for every subnet, `usage` only counts the addresses
+            // inside its own range.
+            let cidrs = subnets.iter().map(|subnet| subnet.cidr).collect();
+            let used = used_addresses(conn, owner_id, cidrs, uniqueness)?;
+            Ok::<_, diesel::result::Error>(
+                subnets
+                    .into_iter()
+                    .map(|subnet| {
+                        let usage = subnet.usage(&used);
+                        (subnet, usage)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+/// Creates a server on the lowest free address of one of the owner's subnets.
+/// The subnet row stays locked until the server is written, so concurrent
+/// allocations from the same subnet are handed different addresses.
+pub async fn allocate<F>(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    subnet_id: Uuid,
+    uniqueness: AddressUniqueness,
+    new_server: F,
+) -> Result<ServerModel, InfraError>
+where
+    F: FnOnce(IpNetwork) -> NewServerDB + Send + 'static,
+{
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    conn.interact(move |conn| {
+        conn.transaction(|conn| {
+            let subnet = subnets::table
+                .filter(subnets::id.eq(subnet_id))
+                .filter(subnets::owner_id.eq(owner_id))
+                .select(SubnetDB::as_select())
+                .for_update()
+                .get_result::<SubnetDB>(conn)?;
+            let subnet = adapt_subnet_db_to_subnet(subnet);
+
+            let used = used_addresses(conn, owner_id, vec![subnet.cidr], uniqueness)?;
+            let address = subnet.next_free(&used).ok_or_else(|| {
+                InfraError::Invalid(format!("Subnet `{}` has no free address left", subnet.cidr))
+            })?;
+
+            insert_one(conn, new_server(IpNetwork::from(address)), uniqueness)
+        })
+    })
+    .await
+    .map_err(adapt_infra_error)?
+}
+
+/// Servers keep the addresses they were given.
+pub async fn delete(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    id: Uuid,
+) -> Result<(), InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let deleted = conn
+        .interact(move |conn| {
+            diesel::delete(
+                subnets::table
+                    .filter(subnets::id.eq(id))
+                    .filter(subnets::owner_id.eq(owner_id)),
+            )
+            .execute(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    if deleted == 0 {
+        return Err(InfraError::NotFound);
+    }
+    Ok(())
+}
+
+fn adapt_subnet_db_to_subnet(subnet_db: SubnetDB) -> SubnetModel {
+    SubnetModel {
+        id: subnet_db.id,
+        cidr: subnet_db.cidr,
+        gateway: subnet_db.gateway.map(|gateway| gateway.ip()),
+        vlan: subnet_db.vlan,
+        description: subnet_db.description,
+        created_at: subnet_db.created_at,
+    }
+}
@@ -0,0 +1,271 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::Timestamptz;
+use diesel::{deserialize::Queryable, Selectable};
+use uuid::Uuid;
+
+use crate::domains::models::server_check::ServerStatus;
+use crate::domains::models::uptime::{
+    weighted_percentile, OutageModel, UptimeReport, UptimeWindow,
+};
+use crate::infra::errors::{adapt_infra_error, InfraError};
+use crate::infra::respository::server_checks::NewServerCheckDB;
+use crate::schema::{server_check_rollups, server_check_samples, server_outages, servers};
+
+#[derive(Insertable)]
+#[diesel(table_name = server_check_samples)]
+pub struct NewServerCheckSampleDB {
+    pub server_id: Uuid,
+    pub checked_at: DateTime<Utc>,
+    pub up: bool,
+    pub latency_ms: Option<i32>,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_check_rollups)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerCheckRollupDB {
+    pub bucket_start: DateTime<Utc>,
+    pub samples: i32,
+    pub up_samples: i32,
+    pub latency_p50_ms: Option<i32>,
+    pub latency_p95_ms: Option<i32>,
+}
+
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = server_outages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServerOutageDB {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = server_outages)]
+pub struct NewServerOutageDB {
+    pub server_id: Uuid,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Appends the checks to the sample series and opens or closes outages. A
+/// degraded server counts as up, an outage lasts as long as no port answers.
+/// Must run in the same transaction that saves the latest checks.
+pub fn record_samples(conn: &mut PgConnection, checks: &[NewServerCheckDB]) -> QueryResult<()> {
+    let is_down = |check: &&NewServerCheckDB| check.status == ServerStatus::Down.as_str();
+
+    let samples: Vec<NewServerCheckSampleDB> = checks
+        .iter()
+        .map(|check| NewServerCheckSampleDB {
+            server_id: check.server_id,
+            checked_at: check.checked_at,
+            up: !is_down(&check),
+            latency_ms: check.latency_ms,
+        })
+        .collect();
+    diesel::insert_into(server_check_samples::table)
+        .values(samples)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    let up_ids: Vec<Uuid> = checks
+        .iter()
+        .filter(|check| !is_down(check))
+        .map(|check| check.server_id)
+        .collect();
+    diesel::update(server_outages::table)
+        .filter(server_outages::server_id.eq_any(up_ids))
+        .filter(server_outages::ended_at.is_null())
+        .set(server_outages::ended_at.eq(Utc::now()))
+        .execute(conn)?;
+
+    // At most one open outage per server, an ongoing one keeps its start.
+    let outages: Vec<NewServerOutageDB> = checks
+        .iter()
+        .filter(is_down)
+        .map(|check| NewServerOutageDB {
+            server_id: check.server_id,
+            started_at: check.checked_at,
+        })
+        .collect();
+    diesel::insert_into(server_outages::table)
+        .values(outages)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Downsamples complete hours into `server_check_rollups`, then drops samples
+/// older than `raw_retention` and rollups and closed outages older than
+/// `rollup_retention`. Hours are recomputed from the newest rollup onwards,
+/// so running it after every check cycle is cheap.
+pub async fn roll_up(
+    pool: &deadpool_diesel::postgres::Pool,
+    raw_retention: Duration,
+    rollup_retention: Duration,
+) -> Result<usize, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let now = Utc::now();
+                let raw_since = now - raw_retention;
+                let rollups_since = now - rollup_retention;
+
+                let rolled_up = diesel::sql_query(
+                    "INSERT INTO server_check_rollups \
+                         (server_id, bucket_start, samples, up_samples, latency_p50_ms, latency_p95_ms) \
+                     SELECT server_id, date_trunc('hour', checked_at), count(*), \
+                         count(*) FILTER (WHERE up), \
+                         percentile_disc(0.5) WITHIN GROUP (ORDER BY latency_ms), \
+                         percentile_disc(0.95) WITHIN GROUP (ORDER BY latency_ms) \
+                     FROM server_check_samples \
+                     WHERE checked_at >= GREATEST( \
+                             (SELECT max(bucket_start) FROM server_check_rollups), $1) \
+                         AND checked_at < date_trunc('hour', $2) \
+                     GROUP BY 1, 2 \
+                     ON CONFLICT (server_id, bucket_start) DO UPDATE SET \
+                         samples = excluded.samples, \
+                         up_samples = excluded.up_samples, \
+                         latency_p50_ms = excluded.latency_p50_ms, \
+                         latency_p95_ms = excluded.latency_p95_ms",
+                )
+                .bind::<Timestamptz, _>(raw_since)
+                .bind::<Timestamptz, _>(now)
+                .execute(conn)?;
+
+                diesel::delete(
+                    server_check_samples::table.filter(server_check_samples::checked_at.lt(raw_since)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    server_check_rollups::table
+                        .filter(server_check_rollups::bucket_start.lt(rollups_since)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    server_outages::table.filter(server_outages::ended_at.lt(rollups_since)),
+                )
+                .execute(conn)?;
+
+                Ok(rolled_up)
+            })
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
+
+/// Availability of one of the owner's servers over the last `window`. Windows
+/// that fit in `raw_retention` are computed from the samples, longer ones from
+/// the hourly rollups plus the samples of the hours not rolled up yet.
+pub async fn get_uptime(
+    pool: &deadpool_diesel::postgres::Pool,
+    owner_id: Uuid,
+    server_id: Uuid,
+    window: UptimeWindow,
+    raw_retention: Duration,
+) -> Result<UptimeReport, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let res = conn
+        .interact(move |conn| {
+            let owned = diesel::select(diesel::dsl::exists(
+                servers::table
+                    .filter(servers::id.eq(server_id))
+                    .filter(servers::owner_id.eq(owner_id)),
+            ))
+            .get_result::<bool>(conn)?;
+
+            if !owned {
+                return Err(diesel::result::Error::NotFound);
+            }
+
+            let to = Utc::now();
+            let from = to - window.duration();
+            let downsampled = window.duration() > raw_retention;
+
+            // (value, weight) pairs for the latency percentiles.
+            let mut p50_latencies = Vec::new();
+            let mut p95_latencies = Vec::new();
+            let mut samples = 0;
+            let mut up_samples = 0;
+
+            let mut raw_since = from;
+            if downsampled {
+                let rollups = server_check_rollups::table
+                    .filter(server_check_rollups::server_id.eq(server_id))
+                    .filter(
+                        server_check_rollups::bucket_start
+                            .ge(from.duration_trunc(Duration::hours(1)).unwrap_or(from)),
+                    )
+                    .select(ServerCheckRollupDB::as_select())
+                    .load::<ServerCheckRollupDB>(conn)?;
+
+                for rollup in rollups {
+                    samples += rollup.samples as i64;
+                    up_samples += rollup.up_samples as i64;
+                    if let Some(p50) = rollup.latency_p50_ms {
+                        p50_latencies.push((p50, rollup.samples as i64));
+                    }
+                    if let Some(p95) = rollup.latency_p95_ms {
+                        p95_latencies.push((p95, rollup.samples as i64));
+                    }
+                    raw_since = raw_since.max(rollup.bucket_start + Duration::hours(1));
+                }
+            }
+
+            let raw = server_check_samples::table
+                .filter(server_check_samples::server_id.eq(server_id))
+                .filter(server_check_samples::checked_at.ge(raw_since))
+                .select((server_check_samples::up, server_check_samples::latency_ms))
+                .load::<(bool, Option<i32>)>(conn)?;
+
+            for (up, latency_ms) in raw {
+                samples += 1;
+                if up {
+                    up_samples += 1;
+                }
+                if let Some(latency_ms) = latency_ms {
+                    p50_latencies.push((latency_ms, 1));
+                    p95_latencies.push((latency_ms, 1));
+                }
+            }
+
+            let outages = server_outages::table
+                .filter(server_outages::server_id.eq(server_id))
+                .filter(
+                    server_outages::ended_at
+                        .is_null()
+                        .or(server_outages::ended_at.gt(from)),
+                )
+                .order(server_outages::started_at.asc())
+                .select(ServerOutageDB::as_select())
+                .load::<ServerOutageDB>(conn)?;
+
+            Ok(UptimeReport {
+                from,
+                to,
+                samples,
+                up_samples,
+                latency_p50_ms: weighted_percentile(p50_latencies, 0.5),
+                latency_p95_ms: weighted_percentile(p95_latencies, 0.95),
+                downsampled,
+                outages: outages
+                    .into_iter()
+                    .map(|outage| OutageModel {
+                        started_at: outage.started_at.max(from),
+                        ended_at: outage.ended_at,
+                    })
+                    .collect(),
+            })
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(res)
+}
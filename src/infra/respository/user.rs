@@ -115,6 +115,26 @@ pub async fn sign_up(
     Ok(response)
 }
 
+pub async fn get_username(
+    pool: &deadpool_diesel::postgres::Pool,
+    user_id: Uuid,
+) -> Result<String, InfraError> {
+    let conn = pool.get().await.map_err(adapt_infra_error)?;
+
+    let username = conn
+        .interact(move |conn| {
+            users::table
+                .filter(users::id.eq(user_id))
+                .select(users::username)
+                .get_result::<String>(conn)
+        })
+        .await
+        .map_err(adapt_infra_error)?
+        .map_err(adapt_infra_error)?;
+
+    Ok(username)
+}
+
 impl From<InfraError> for SignUpError {
     fn from(error: InfraError) -> Self {
         match error {
@@ -122,6 +142,14 @@ impl From<InfraError> for SignUpError {
                 SignUpError::InfraError(InfraError::InternalServerError)
             }
             InfraError::NotFound => SignUpError::InfraError(InfraError::NotFound),
+            InfraError::Conflict => SignUpError::InfraError(InfraError::Conflict),
+            // Someone else signed up with the name since it was checked.
+            InfraError::UniqueViolation(_) => SignUpError::UsernameAlreadyExists,
+            InfraError::Invalid(message) => SignUpError::InfraError(InfraError::Invalid(message)),
+            InfraError::Duplicate(message) => {
+                SignUpError::InfraError(InfraError::Duplicate(message))
+            }
+            InfraError::InUse(message) => SignUpError::InfraError(InfraError::InUse(message)),
         }
     }
 }
@@ -0,0 +1,84 @@
+use std::fmt;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// Seals secret values with AES-256-GCM under the master key. Callers pass
+/// associated data naming the entry, so a sealed value cannot be opened as
+/// another entry.
+#[derive(Clone)]
+pub struct Vault {
+    key: Arc<LessSafeKey>,
+    rng: SystemRandom,
+}
+
+/// A value as stored: the random nonce and the ciphertext with its tag.
+pub struct Sealed {
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+}
+
+impl Vault {
+    pub fn new(master_key: &[u8; 32]) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, master_key).expect("AES-256 keys are 32 bytes");
+        Vault {
+            key: Arc::new(LessSafeKey::new(key)),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    pub fn seal(&self, aad: &str, value: &str) -> Result<Sealed, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| String::from("no randomness for a nonce"))?;
+
+        let mut ciphertext = value.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad.as_bytes()),
+                &mut ciphertext,
+            )
+            .map_err(|_| String::from("sealing failed"))?;
+
+        Ok(Sealed {
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Fails when the value was sealed under another key or other associated
+    /// data, or has been tampered with.
+    pub fn open(&self, aad: &str, sealed: Sealed) -> Result<String, String> {
+        let nonce = Nonce::try_assume_unique_for_key(&sealed.nonce)
+            .map_err(|_| String::from("stored nonce has the wrong length"))?;
+
+        let mut ciphertext = sealed.ciphertext;
+        let value = self
+            .key
+            .open_in_place(nonce, Aad::from(aad.as_bytes()), &mut ciphertext)
+            .map_err(|_| String::from("the value does not open with the master key"))?;
+
+        String::from_utf8(value.to_vec()).map_err(|_| String::from("the value is not UTF-8"))
+    }
+}
+
+impl fmt::Debug for Vault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Vault")
+    }
+}
+
+/// Reads a master key given as 32 bytes in standard base64, e.g. the output
+/// of `openssl rand -base64 32`.
+pub fn parse_master_key(value: &str) -> Result<[u8; 32], String> {
+    STANDARD
+        .decode(value.trim())
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .ok_or_else(|| String::from("VAULT_MASTER_KEY must be 32 bytes encoded as base64"))
+}
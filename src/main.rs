@@ -28,6 +28,9 @@ pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 #[derive(Clone)]
 pub struct AppState {
     pool: Pool,
+    resolver: infra::dns::Resolver,
+    /// `None` when no `VAULT_MASTER_KEY` is configured.
+    vault: Option<infra::vault::Vault>,
     jwt_secret: Arc<Mutex<Option<String>>>,
 }
 
@@ -47,8 +50,35 @@ async fn main() {
         run_migrations(&pool).await;
     }
 
+    tokio::spawn(infra::jobs::trash_purge::run(
+        pool.clone(),
+        config.trash_retention(),
+        config.trash_purge_interval(),
+    ));
+
+    tokio::spawn(infra::jobs::health_check::run(
+        pool.clone(),
+        infra::jobs::health_check::HealthCheckSettings {
+            every: config.health_check_interval(),
+            timeout: config.health_check_timeout(),
+            concurrency: config.health_check_concurrency(),
+            default_ports: config.health_check_ports().to_vec(),
+            raw_retention: config.uptime_raw_retention(),
+            rollup_retention: config.uptime_rollup_retention(),
+        },
+    ));
+
+    let resolver = infra::dns::Resolver::new(config.dns_hosts_file(), config.dns_check_timeout());
+    tokio::spawn(infra::jobs::dns_check::run(
+        pool.clone(),
+        resolver.clone(),
+        config.dns_check_interval(),
+    ));
+
     let state: AppState = AppState {
         pool,
+        resolver,
+        vault: config.vault_master_key().map(infra::vault::Vault::new),
         jwt_secret: Arc::new(Mutex::new(None)),
     };
 
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use axum::http::{StatusCode, Uri};
 use axum::response::{Html, IntoResponse};
-use axum::routing::{delete, get, get_service, post};
+use axum::routing::{delete, get, get_service, post, put};
 use axum::{middleware, Router};
 use tower_http::trace::DefaultMakeSpan;
 use tower_http::{
@@ -10,11 +10,64 @@ use tower_http::{
     trace::TraceLayer,
 };
 
+use crate::handlers::api_keys::create_api_key::create_api_key;
+use crate::handlers::api_keys::list_api_keys::list_api_keys;
+use crate::handlers::api_keys::revoke_api_key::revoke_api_key;
+use crate::handlers::comments::create_comment::create_comment;
+use crate::handlers::comments::delete_comment::delete_comment;
+use crate::handlers::comments::list_comments::list_comments;
+use crate::handlers::comments::update_comment::update_comment;
+use crate::handlers::dependencies::create_dependency::create_dependency;
+use crate::handlers::dependencies::delete_dependency::delete_dependency;
+use crate::handlers::dependencies::list_dependencies::list_dependencies;
+use crate::handlers::dependencies::list_dependents::list_dependents;
+use crate::handlers::inventory::ansible_inventory::ansible_inventory;
+use crate::handlers::maintenance::active_maintenance::active_maintenance;
+use crate::handlers::maintenance::create_maintenance_window::create_maintenance_window;
+use crate::handlers::maintenance::delete_maintenance_window::delete_maintenance_window;
+use crate::handlers::maintenance::list_maintenance_windows::list_maintenance_windows;
+use crate::handlers::metadata_schemas::create_metadata_schema::create_metadata_schema;
+use crate::handlers::metadata_schemas::delete_metadata_schema::delete_metadata_schema;
+use crate::handlers::metadata_schemas::list_metadata_schemas::list_metadata_schemas;
+use crate::handlers::metadata_schemas::update_metadata_schema::update_metadata_schema;
+use crate::handlers::pins::list_pins::list_pins;
+use crate::handlers::pins::pin_server::pin_server;
+use crate::handlers::pins::unpin_server::unpin_server;
+use crate::handlers::sd::prometheus_sd::prometheus_sd;
+use crate::handlers::secrets::delete_secret::delete_secret;
+use crate::handlers::secrets::list_secrets::list_secrets;
+use crate::handlers::secrets::put_secret::put_secret;
+use crate::handlers::secrets::reveal_secret::reveal_secret;
+use crate::handlers::servers::bulk_create_servers::bulk_create_servers;
+use crate::handlers::servers::bulk_delete_servers::bulk_delete_servers;
 use crate::handlers::servers::create_server::create_server;
 use crate::handlers::servers::delete_server::delete_server;
+use crate::handlers::servers::diff_server_history::diff_server_history;
+use crate::handlers::servers::dns_mismatches::dns_mismatches;
+use crate::handlers::servers::duplicate_servers::duplicate_servers;
+use crate::handlers::servers::export_servers::export_servers;
+use crate::handlers::servers::export_ssh_config::export_ssh_config;
+use crate::handlers::servers::get_server_uptime::get_server_uptime;
+use crate::handlers::servers::import_servers::import_servers;
+use crate::handlers::servers::list_server_history::list_server_history;
 use crate::handlers::servers::list_servers::list_servers;
+use crate::handlers::servers::list_trash::list_trash;
+use crate::handlers::servers::restore_server::restore_server;
+use crate::handlers::servers::suggest_servers::suggest_servers;
+use crate::handlers::servers::update_server::update_server;
+use crate::handlers::services::create_service::create_service;
+use crate::handlers::services::delete_service::delete_service;
+use crate::handlers::services::list_services::list_services;
+use crate::handlers::services::update_service::update_service;
+use crate::handlers::subnets::allocate_address::allocate_address;
+use crate::handlers::subnets::create_subnet::create_subnet;
+use crate::handlers::subnets::delete_subnet::delete_subnet;
+use crate::handlers::subnets::list_subnets::list_subnets;
 use crate::handlers::user::{sign_in, sign_out, sign_up};
-use crate::infra::middleware::auth_middleware::jwt_token_check;
+use crate::handlers::views::create_view::create_view;
+use crate::handlers::views::delete_view::delete_view;
+use crate::handlers::views::list_views::list_views;
+use crate::infra::middleware::auth_middleware::{admin_check, api_key_check, jwt_token_check};
 use crate::AppState;
 
 pub fn app_router(state: Arc<AppState>) -> Router {
@@ -22,6 +75,16 @@ pub fn app_router(state: Arc<AppState>) -> Router {
         .nest_service("/", routes_static())
         .nest("/v1/servers", servers_routes(state.clone()))
         .nest("/v1/main_user", main_user_routers(state.clone()))
+        .nest("/v1/main_user/api_keys", api_keys_routes(state.clone()))
+        .nest("/v1/me", me_routes(state.clone()))
+        .nest("/v1/inventory", inventory_routes(state.clone()))
+        .nest(
+            "/v1/metadata_schemas",
+            metadata_schemas_routes(state.clone()),
+        )
+        .nest("/v1/subnets", subnets_routes(state.clone()))
+        .nest("/v1/sd", sd_routes(state.clone()))
+        .nest("/v1/maintenance", maintenance_routes(state.clone()))
         .fallback(handler_404)
 }
 
@@ -43,11 +106,124 @@ fn servers_routes(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/", post(create_server))
         .route("/", get(list_servers))
-        .route("/:id", delete(delete_server))
+        .route(
+            "/bulk",
+            post(bulk_create_servers).delete(bulk_delete_servers),
+        )
+        .route("/export.csv", get(export_servers))
+        .route("/import", post(import_servers))
+        .route("/dns_mismatches", get(dns_mismatches))
+        .route("/duplicates", get(duplicate_servers))
+        .route("/ssh_config", get(export_ssh_config))
+        .route("/suggest", get(suggest_servers))
+        .route("/trash", get(list_trash))
+        .route("/:id/restore", post(restore_server))
+        .route("/:id/history", get(list_server_history))
+        .route("/:id/history/diff", get(diff_server_history))
+        .route("/:id/uptime", get(get_server_uptime))
+        .route(
+            "/:id/maintenance",
+            post(create_maintenance_window).get(list_maintenance_windows),
+        )
+        .route(
+            "/:id/maintenance/:window_id",
+            delete(delete_maintenance_window),
+        )
+        .route("/:id/comments", post(create_comment).get(list_comments))
+        .route(
+            "/:id/comments/:comment_id",
+            delete(delete_comment).patch(update_comment),
+        )
+        .route(
+            "/:id/dependencies",
+            post(create_dependency).get(list_dependencies),
+        )
+        .route(
+            "/:id/dependencies/:depends_on_id",
+            delete(delete_dependency),
+        )
+        .route("/:id/dependents", get(list_dependents))
+        .route("/:id/secrets", get(list_secrets))
+        .route("/:id/secrets/:name", put(put_secret).delete(delete_secret))
+        .route("/:id/secrets/:name/reveal", post(reveal_secret))
+        .route("/:id/services", post(create_service).get(list_services))
+        .route(
+            "/:id/services/:service_id",
+            delete(delete_service).patch(update_service),
+        )
+        .route("/:id", delete(delete_server).patch(update_server))
         .with_state(state)
         .route_layer(middleware::from_fn(jwt_token_check))
 }
 
+fn inventory_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/ansible", get(ansible_inventory))
+        .with_state(state)
+        .route_layer(middleware::from_fn(jwt_token_check))
+}
+
+fn api_keys_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", post(create_api_key).get(list_api_keys))
+        .route("/:id", delete(revoke_api_key))
+        .with_state(state)
+        .route_layer(middleware::from_fn(jwt_token_check))
+}
+
+/// Preferences of the signed in user: saved views and pinned servers.
+fn me_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/views", post(create_view).get(list_views))
+        .route("/views/:id", delete(delete_view))
+        .route("/pins", get(list_pins))
+        .route("/pins/:server_id", put(pin_server).delete(unpin_server))
+        .with_state(state)
+        .route_layer(middleware::from_fn(jwt_token_check))
+}
+
+/// Everyone can read the schemas their servers are checked against, only admins change them.
+fn metadata_schemas_routes(state: Arc<AppState>) -> Router {
+    let admin_routes = Router::new()
+        .route("/", post(create_metadata_schema))
+        .route(
+            "/:id",
+            delete(delete_metadata_schema).patch(update_metadata_schema),
+        )
+        .with_state(state.clone())
+        .route_layer(middleware::from_fn_with_state(state.clone(), admin_check));
+
+    Router::new()
+        .route("/", get(list_metadata_schemas))
+        .with_state(state)
+        .merge(admin_routes)
+        .route_layer(middleware::from_fn(jwt_token_check))
+}
+
+fn subnets_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/", post(create_subnet).get(list_subnets))
+        .route("/:id/allocate", post(allocate_address))
+        .route("/:id", delete(delete_subnet))
+        .with_state(state)
+        .route_layer(middleware::from_fn(jwt_token_check))
+}
+
+fn sd_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/prometheus", get(prometheus_sd))
+        .with_state(state.clone())
+        .route_layer(middleware::from_fn_with_state(state, api_key_check))
+}
+
+/// Read by monitors, which authenticate with an API key like service discovery.
+fn maintenance_routes(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/active", get(active_maintenance))
+        .with_state(state.clone())
+        .route_layer(middleware::from_fn_with_state(state, api_key_check))
+}
+
 fn routes_static() -> Router {
     Router::new()
         .nest_service("/", get_service(ServeDir::new("www/")))
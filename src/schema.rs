@@ -1,12 +1,218 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    metadata_schemas (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        label_selector -> Text,
+        schema -> Jsonb,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    saved_views (id) {
+        id -> Uuid,
+        owner_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        filter -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_check_rollups (server_id, bucket_start) {
+        server_id -> Uuid,
+        bucket_start -> Timestamptz,
+        samples -> Int4,
+        up_samples -> Int4,
+        latency_p50_ms -> Nullable<Int4>,
+        latency_p95_ms -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    server_check_samples (server_id, checked_at) {
+        server_id -> Uuid,
+        checked_at -> Timestamptz,
+        up -> Bool,
+        latency_ms -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    server_checks (server_id) {
+        server_id -> Uuid,
+        #[max_length = 16]
+        status -> Varchar,
+        latency_ms -> Nullable<Int4>,
+        ports -> Jsonb,
+        checked_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_comments (id) {
+        id -> Uuid,
+        server_id -> Uuid,
+        author_id -> Nullable<Uuid>,
+        body -> Text,
+        created_at -> Timestamptz,
+        edited_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    server_dependencies (server_id, depends_on_id) {
+        server_id -> Uuid,
+        depends_on_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_dns_checks (server_id) {
+        server_id -> Uuid,
+        #[max_length = 253]
+        hostname -> Nullable<Varchar>,
+        ip -> Inet,
+        addresses -> Array<Inet>,
+        forward_error -> Nullable<Text>,
+        ptr_names -> Array<Text>,
+        reverse_error -> Nullable<Text>,
+        checked_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_history (id) {
+        id -> Int8,
+        server_id -> Uuid,
+        revision -> Int4,
+        #[max_length = 16]
+        action -> Varchar,
+        actor_id -> Nullable<Uuid>,
+        changed_at -> Timestamptz,
+        before -> Nullable<Jsonb>,
+        after -> Nullable<Jsonb>,
+    }
+}
+
+diesel::table! {
+    server_maintenance_windows (id) {
+        id -> Uuid,
+        server_id -> Uuid,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+        rrule -> Nullable<Text>,
+        reason -> Nullable<Text>,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_outages (id) {
+        id -> Int8,
+        server_id -> Uuid,
+        started_at -> Timestamptz,
+        ended_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    server_pins (user_id, server_id) {
+        user_id -> Uuid,
+        server_id -> Uuid,
+        pinned_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_secret_reveals (id) {
+        id -> Int8,
+        server_id -> Uuid,
+        #[max_length = 255]
+        secret_name -> Varchar,
+        actor_id -> Nullable<Uuid>,
+        succeeded -> Bool,
+        revealed_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_secrets (server_id, name) {
+        server_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        nonce -> Bytea,
+        ciphertext -> Bytea,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    server_services (id) {
+        id -> Uuid,
+        server_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        port -> Int4,
+        #[max_length = 8]
+        protocol -> Varchar,
+        #[max_length = 255]
+        team -> Nullable<Varchar>,
+        url -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     servers (id) {
         id -> Uuid,
         #[max_length = 255]
         name -> Varchar,
+        ip -> Inet,
+        version -> Int4,
+        owner_id -> Nullable<Uuid>,
+        labels -> Jsonb,
+        created_at -> Timestamptz,
+        deleted_at -> Nullable<Timestamptz>,
+        check_ports -> Array<Int4>,
+        metadata -> Jsonb,
+        #[max_length = 253]
+        hostname -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    subnets (id) {
+        id -> Uuid,
+        owner_id -> Uuid,
+        cidr -> Cidr,
+        gateway -> Nullable<Inet>,
+        vlan -> Nullable<Int4>,
+        description -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        token -> Text,
         #[max_length = 255]
-        ip -> Varchar,
+        name -> Varchar,
+        created_at -> Timestamptz,
+        last_used_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -19,7 +225,48 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(metadata_schemas -> users (created_by));
+diesel::joinable!(saved_views -> users (owner_id));
+diesel::joinable!(server_check_rollups -> servers (server_id));
+diesel::joinable!(server_check_samples -> servers (server_id));
+diesel::joinable!(server_checks -> servers (server_id));
+diesel::joinable!(server_comments -> servers (server_id));
+diesel::joinable!(server_comments -> users (author_id));
+diesel::joinable!(server_dependencies -> users (created_by));
+diesel::joinable!(server_dns_checks -> servers (server_id));
+diesel::joinable!(server_history -> servers (server_id));
+diesel::joinable!(server_history -> users (actor_id));
+diesel::joinable!(server_maintenance_windows -> servers (server_id));
+diesel::joinable!(server_maintenance_windows -> users (created_by));
+diesel::joinable!(server_outages -> servers (server_id));
+diesel::joinable!(server_pins -> servers (server_id));
+diesel::joinable!(server_pins -> users (user_id));
+diesel::joinable!(server_secret_reveals -> users (actor_id));
+diesel::joinable!(server_secrets -> servers (server_id));
+diesel::joinable!(server_secrets -> users (created_by));
+diesel::joinable!(server_services -> servers (server_id));
+diesel::joinable!(servers -> users (owner_id));
+diesel::joinable!(subnets -> users (owner_id));
+diesel::joinable!(user_tokens -> users (user_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    metadata_schemas,
+    saved_views,
+    server_check_rollups,
+    server_check_samples,
+    server_checks,
+    server_comments,
+    server_dependencies,
+    server_dns_checks,
+    server_history,
+    server_maintenance_windows,
+    server_outages,
+    server_pins,
+    server_secret_reveals,
+    server_secrets,
+    server_services,
     servers,
+    subnets,
+    user_tokens,
     users,
 );
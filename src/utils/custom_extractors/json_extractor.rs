@@ -11,6 +11,6 @@ impl From<JsonRejection> for AppError {
     fn from(rejection: JsonRejection) -> Self {
         debug!("JsonRejection: {:?}", rejection);
 
-        AppError::BodyParsingError(rejection.to_string())
+        AppError::BodyParsingError(rejection.body_text())
     }
 }
@@ -1,2 +1,3 @@
 pub mod json_extractor;
 pub mod path_extractor;
+pub mod query_extractor;
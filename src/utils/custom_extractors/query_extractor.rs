@@ -0,0 +1,17 @@
+use axum::extract::rejection::QueryRejection;
+use axum_macros::FromRequestParts;
+use tracing::debug;
+
+use crate::errors::AppError;
+
+#[derive(FromRequestParts, Debug)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct QueryExtractor<T>(pub T);
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        debug!("QueryRejection: {:?}", rejection);
+
+        AppError::BodyParsingError(rejection.body_text())
+    }
+}
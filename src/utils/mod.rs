@@ -1,5 +1,8 @@
 pub use custom_extractors::json_extractor::JsonExtractor;
 pub use custom_extractors::path_extractor::PathExtractor;
+pub use custom_extractors::query_extractor::QueryExtractor;
+pub use digest::sha256_hex;
 
 mod custom_extractors;
+mod digest;
 